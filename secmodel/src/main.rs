@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand};
+use secmodel_core::{Finding, ModelError};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -8,7 +9,7 @@ struct Cli {
         short,
         env = "SECMODEL",
         default_value = "secmodel.toml",
-        help = "The security model to use"
+        help = "The security model to use, a file or a directory of fragments"
     )]
     model: String,
     #[command(subcommand)]
@@ -17,8 +18,30 @@ struct Cli {
 
 #[derive(Subcommand, Debug)]
 enum Commands {
-    Check {},
-    Mermaid {},
+    Check {
+        #[arg(
+            long,
+            help = "A file of policy rules to check the model against, may be repeated"
+        )]
+        rules: Vec<String>,
+    },
+    Lineage {
+        #[arg(help = "The data to follow through the model, e.g. data.health-info")]
+        data: String,
+    },
+    Reach {
+        #[arg(help = "The server or endpoint traffic starts from")]
+        from: String,
+        #[arg(help = "The server or endpoint it is to reach")]
+        to: String,
+    },
+    Mermaid {
+        #[arg(
+            long,
+            help = "Highlight the attack surface of a threat, e.g. threat.criminals"
+        )]
+        threat: Option<String>,
+    },
     Report {},
 }
 
@@ -26,23 +49,74 @@ fn main() {
     let cli = Cli::parse();
     let model = match secmodel_core::load(&cli.model) {
         Ok(m) => m,
-        Err(e) => {
-            eprintln!("Unable to load model {}: {e}", cli.model);
-            std::process::exit(1);
-        }
+        Err(e) => load_failed(&cli.model, e),
     };
 
     match &cli.command {
-        Commands::Check {} => {
-            println!("{}", serde_json::to_string_pretty(&model).unwrap());
-
-            std::process::exit(0);
-        }
-        Commands::Mermaid {} => {
+        Commands::Check { rules } => {
+            let mut diagnostics = secmodel_core::validate(&model);
+            let findings = secmodel_core::evaluate(&model);
+            diagnostics.extend(findings.iter().map(Finding::diagnostic));
+            for d in &diagnostics {
+                println!("{}", d.render());
+            }
+            for path in rules {
+                let policy = match secmodel_core::load_policy(path) {
+                    Ok(p) => p,
+                    Err(e) => load_failed(path, e),
+                };
+                for rule in &policy {
+                    println!("{path}: rule {}", rule.name);
+                    let outcomes = rule.outcomes(&model);
+                    for outcome in &outcomes {
+                        if outcome.passed() {
+                            println!("  pass {}", outcome.entity);
+                        } else {
+                            println!("  fail {}: {}", outcome.entity, outcome.failures.join(", "));
+                        }
+                    }
+                    let findings = rule.findings(&model, outcomes);
+                    diagnostics.extend(findings.iter().map(Finding::diagnostic));
+                }
+            }
+            let errors = diagnostics.iter().filter(|d| d.is_error()).count();
             println!(
-                "{}",
-                secmodel_mermaid::Render::render(&model, "", &model).unwrap()
+                "{}: {errors} errors, {} warnings",
+                cli.model,
+                diagnostics.len() - errors
             );
+            std::process::exit(i32::from(errors > 0));
+        }
+        Commands::Lineage { data } => match secmodel_core::lineage(&model, data) {
+            Ok(lineage) => print!("{lineage}"),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        },
+        Commands::Reach { from, to } => match secmodel_core::reach(&model, from, to) {
+            Ok(Some(route)) => println!("{route}"),
+            Ok(None) => {
+                println!("{from} cannot reach {to}");
+                std::process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        },
+        Commands::Mermaid { threat } => {
+            let mut diagram = secmodel_mermaid::Render::render(&model, "", &model).unwrap();
+            if let Some(threat) = threat {
+                match secmodel_core::attack_surface(&model, threat) {
+                    Ok(surface) => diagram.push_str(&secmodel_mermaid::highlight_surface(&surface)),
+                    Err(e) => {
+                        eprintln!("{e}");
+                        std::process::exit(1);
+                    }
+                }
+            }
+            println!("{diagram}");
             std::process::exit(0);
         }
         Commands::Report {} => {
@@ -51,3 +125,17 @@ fn main() {
         }
     }
 }
+
+fn load_failed(path: &str, e: ModelError) -> ! {
+    match e {
+        ModelError::ParseError(d) => eprint!("{}", d.render()),
+        ModelError::MergeError(diagnostics) => {
+            for d in &diagnostics {
+                eprintln!("{}", d.render());
+            }
+            eprintln!("Unable to load {path}");
+        }
+        e => eprintln!("Unable to load {path}: {e}"),
+    }
+    std::process::exit(1);
+}
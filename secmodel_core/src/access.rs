@@ -0,0 +1,285 @@
+use crate::reach::reachable_networks;
+use crate::reference::kind_of;
+use crate::rules::{Finding, Rule};
+use crate::surface::locations;
+use crate::{
+    AuthenticationID, AuthorizationID, ChannelID, EntityRef, Model, NetworkID, Relation, Severity,
+};
+use indexmap::IndexSet;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+
+// What an entity reachable from an untrusted network lacks.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum AccessGap {
+    // no authentication scheme
+    Unauthenticated,
+    // an authentication scheme, but no authorization scheme
+    Unauthorized,
+}
+
+impl fmt::Display for AccessGap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessGap::Unauthenticated => write!(f, "no authentication"),
+            AccessGap::Unauthorized => write!(f, "authentication but no authorization"),
+        }
+    }
+}
+
+// An application, store or channel which can be reached from an untrusted
+// network without both authentication and authorization.
+#[derive(Serialize, Debug, Clone)]
+pub struct AccessCoverage {
+    pub entity: EntityRef,
+    pub gap: AccessGap,
+    // the untrusted networks it can be reached from
+    pub networks: Vec<NetworkID>,
+}
+
+// The networks in a boundary with a trust of 0, and those threats act
+// from.
+pub fn untrusted_networks(model: &Model) -> Vec<NetworkID> {
+    let mut networks: IndexSet<&NetworkID> = IndexSet::new();
+    for boundary in model.boundary.iter().flatten().map(|(_, b)| b) {
+        if boundary.trust == Some(0) {
+            networks.extend(boundary.networks.iter().flatten());
+        }
+    }
+    for threat in model.threat.iter().flatten().map(|(_, t)| t) {
+        networks.extend(threat.networks.iter().flatten());
+    }
+    networks.into_iter().cloned().collect()
+}
+
+// Every application, store and channel reachable from an untrusted network
+// which lacks authentication or authorization, in model order.  Stores and
+// channels name their own schemes, while an application has those of the
+// channels of the flows to it, and is only as covered as the least covered
+// of them.
+pub fn access_coverage(model: &Model) -> Vec<AccessCoverage> {
+    let untrusted = untrusted_networks(model);
+    let reachable: Vec<(&NetworkID, Vec<&str>)> = untrusted
+        .iter()
+        .map(|n| {
+            let networks = reachable_networks(model, n, std::iter::once(n.as_str()));
+            (n, networks)
+        })
+        .collect();
+
+    let mut coverage = Vec::new();
+    for id in model.entity_ids() {
+        let Some((authenticated, authorized)) = schemes(model, &id) else {
+            continue;
+        };
+        let gap = match (authenticated, authorized) {
+            (false, _) => AccessGap::Unauthenticated,
+            (true, false) => AccessGap::Unauthorized,
+            (true, true) => continue,
+        };
+        let located = locations(model, &id, &mut HashSet::new());
+        let networks: Vec<NetworkID> = reachable
+            .iter()
+            .filter(|(_, networks)| located.iter().any(|l| networks.contains(l)))
+            .map(|(n, _)| (*n).clone())
+            .collect();
+        if networks.is_empty() {
+            continue;
+        }
+        let Ok(entity) = id.parse() else {
+            continue;
+        };
+        coverage.push(AccessCoverage {
+            entity,
+            gap,
+            networks,
+        });
+    }
+    coverage
+}
+
+// The stores and channels naming an authentication or authorization
+// scheme, in model order.
+pub fn scheme_users(model: &Model, scheme: &str) -> Vec<EntityRef> {
+    model
+        .graph()
+        .reverse_neighbors(scheme, Relation::Uses)
+        .into_iter()
+        .filter(|e| matches!(e.kind(), "store" | "channel"))
+        .cloned()
+        .collect()
+}
+
+// whether an application, store or channel is authenticated and
+// authorized, or nothing for other entities
+fn schemes(model: &Model, id: &str) -> Option<(bool, bool)> {
+    match kind_of(id) {
+        "store" => {
+            let store = model.store_by_id(id)?;
+            Some((
+                store.authentication.is_some(),
+                store.authorization.is_some(),
+            ))
+        }
+        "channel" => {
+            let (authentication, authorization) = channel_schemes(model, &id.parse().ok()?);
+            Some((authentication.is_some(), authorization.is_some()))
+        }
+        "application" => {
+            let graph = model.graph();
+            let mut covered = (false, false);
+            let mut first = true;
+            for flow in graph.reverse_neighbors(id, Relation::FlowsTo) {
+                let channel = model.flow_by_id(flow).and_then(|f| f.channel.as_ref());
+                let (authentication, authorization) = match channel {
+                    Some(c) => channel_schemes(model, c),
+                    None => (None, None),
+                };
+                let flow_covered = (authentication.is_some(), authorization.is_some());
+                covered = if first {
+                    flow_covered
+                } else {
+                    (covered.0 && flow_covered.0, covered.1 && flow_covered.1)
+                };
+                first = false;
+            }
+            Some(covered)
+        }
+        _ => None,
+    }
+}
+
+// the authentication and authorization of a channel, or failing that, of
+// the nearest channel it is carried over which has them
+pub(crate) fn channel_schemes(
+    model: &Model,
+    channel: &ChannelID,
+) -> (Option<AuthenticationID>, Option<AuthorizationID>) {
+    let stack = model.channel_stack(channel);
+    (
+        stack.iter().find_map(|c| c.authentication.clone()),
+        stack.iter().find_map(|c| c.authorization.clone()),
+    )
+}
+
+// Applications, stores and channels reachable from an untrusted network
+// without authentication, or authenticated but not authorized.
+pub struct UncontrolledAccess;
+
+impl Rule for UncontrolledAccess {
+    fn name(&self) -> &'static str {
+        "uncontrolled-access"
+    }
+
+    fn evaluate(&self, model: &Model) -> Vec<Finding> {
+        access_coverage(model)
+            .into_iter()
+            .map(|c| {
+                let (severity, field) = match c.gap {
+                    AccessGap::Unauthenticated => (Severity::Error, "authentication"),
+                    AccessGap::Unauthorized => (Severity::Warning, "authorization"),
+                };
+                let networks = c
+                    .networks
+                    .iter()
+                    .map(|n| format!("`{n}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let id = c.entity.as_str();
+                let mut entities = vec![c.entity.clone()];
+                entities.extend(c.networks.iter().map(EntityRef::untyped));
+                Finding {
+                    rule: self.name().to_string(),
+                    severity,
+                    message: format!("`{id}` has {} and is reachable from {networks}", c.gap),
+                    entities,
+                    location: model
+                        .sources
+                        .locate(&format!("{id}.{field}"))
+                        .or_else(|| model.sources.locate(id)),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coverage() {
+        let model: Model = toml::from_str(
+            r#"
+[network.internet]
+[network.dmz]
+[network.prod]
+controls = { ingress_default = "deny" }
+[boundary.outside]
+trust = 0
+networks = ["network.internet"]
+
+[server.fw]
+interfaces = [{ network = "network.internet" }, { network = "network.dmz" }]
+[server.web]
+interfaces = [{ network = "network.dmz" }]
+applications = ["application.site", "application.api"]
+stores = ["store.cache", "store.files"]
+[server.db]
+interfaces = [{ network = "network.prod" }]
+stores = ["store.db"]
+[endpoint.browser]
+interfaces = [{ network = "network.internet" }]
+
+[authentication.password]
+[authorization.rbac]
+[channel.tcp]
+[channel.https]
+over = ["channel.tcp"]
+authentication = "authentication.password"
+[channel.api]
+over = ["channel.https"]
+authorization = "authorization.rbac"
+
+[application.site]
+[application.api]
+[store.cache]
+authentication = "authentication.password"
+[store.files]
+authentication = "authentication.password"
+authorization = "authorization.rbac"
+[store.db]
+
+[flow.browse]
+sources = ["endpoint.browser"]
+destinations = ["application.site"]
+channel = "channel.https"
+[flow.call]
+sources = ["endpoint.browser"]
+destinations = ["application.api"]
+channel = "channel.api"
+"#,
+        )
+        .unwrap();
+        let summary: Vec<(String, AccessGap)> = access_coverage(&model)
+            .into_iter()
+            .map(|c| (c.entity.to_string(), c.gap))
+            .collect();
+        // the database is behind a network which lets nothing in
+        assert_eq!(
+            vec![
+                ("application.site".to_string(), AccessGap::Unauthorized),
+                ("channel.tcp".to_string(), AccessGap::Unauthenticated),
+                ("channel.https".to_string(), AccessGap::Unauthorized),
+                ("store.cache".to_string(), AccessGap::Unauthorized),
+            ],
+            summary
+        );
+        assert_eq!(
+            scheme_users(&model, "authorization.rbac"),
+            vec!["store.files", "channel.api"]
+        );
+    }
+}
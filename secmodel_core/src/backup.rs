@@ -0,0 +1,162 @@
+use crate::reach::hosts_of;
+use crate::rules::{regulated, Finding, Rule};
+use crate::{EntityRef, Model, Severity, StoreID};
+
+// Stores holding regulated data without a backup, or whose backup is not
+// offsite or has never been restored from.
+pub struct UnverifiedOffsiteBackup;
+
+impl Rule for UnverifiedOffsiteBackup {
+    fn name(&self) -> &'static str {
+        "unverified-offsite-backup"
+    }
+
+    fn evaluate(&self, model: &Model) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for (name, store) in model.store.iter().flatten() {
+            let id = StoreID::named(name);
+            let Some(data) = store.data.iter().flatten().find(|d| regulated(model, d)) else {
+                continue;
+            };
+            let (severity, problem) = match &store.backup {
+                None => (Severity::Error, "has no backup".to_string()),
+                Some(backup) => match backup.resolve(model) {
+                    Some(b) if b.offsite != Some(true) => {
+                        (Severity::Warning, format!("`{backup}` is not offsite"))
+                    }
+                    Some(b) if b.verified.is_none() => (
+                        Severity::Warning,
+                        format!("no restore from `{backup}` has been verified"),
+                    ),
+                    // verified offsite, or missing, which validation reports
+                    _ => continue,
+                },
+            };
+            let mut entities = vec![id.untyped(), data.untyped()];
+            entities.extend(store.backup.iter().map(EntityRef::untyped));
+            findings.push(Finding {
+                rule: self.name().to_string(),
+                severity,
+                message: format!("`{id}` holds regulated `{data}`, but {problem}"),
+                entities,
+                location: model
+                    .sources
+                    .locate(&format!("{id}.backup"))
+                    .or_else(|| model.sources.locate(id.as_str())),
+            });
+        }
+        findings
+    }
+}
+
+// Stores whose backup is written to a store on the same server or endpoint
+// as they are, and so is lost along with them.
+pub struct BackupOnSameServer;
+
+impl Rule for BackupOnSameServer {
+    fn name(&self) -> &'static str {
+        "backup-on-same-server"
+    }
+
+    fn evaluate(&self, model: &Model) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for (name, store) in model.store.iter().flatten() {
+            let id = StoreID::named(name);
+            let Some(backup) = &store.backup else {
+                continue;
+            };
+            let Some(target) = backup.resolve(model).and_then(|b| b.target.as_ref()) else {
+                continue;
+            };
+            let hosts = hosts_of(model, id.as_str());
+            let Some(host) = hosts_of(model, target.as_str())
+                .into_iter()
+                .find(|h| hosts.contains(h))
+            else {
+                continue;
+            };
+            let mut entities = vec![id.untyped(), backup.untyped(), target.untyped()];
+            entities.extend(host.parse::<EntityRef>().ok());
+            findings.push(Finding {
+                rule: self.name().to_string(),
+                severity: Severity::Warning,
+                message: format!(
+                    "`{id}` is backed up by `{backup}` to `{target}`, both on `{host}`"
+                ),
+                entities,
+                location: model.sources.locate(&format!("{backup}.target")),
+            });
+        }
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluate_rules;
+
+    #[test]
+    fn backups() {
+        let model: Model = toml::from_str(
+            r#"
+[regulation.hipaa]
+[data.record]
+regulations = ["regulation.hipaa"]
+[data.logs]
+
+[server.db]
+stores = ["store.db", "store.snapshots"]
+[server.vault]
+stores = ["store.vault"]
+
+[store.db]
+data = ["data.record"]
+backup = "backup.snapshot"
+[store.snapshots]
+[store.vault]
+[store.archive]
+data = ["data.record"]
+backup = "backup.offsite"
+[store.verified]
+data = ["data.record"]
+backup = "backup.verified"
+[store.unbacked]
+data = ["data.record"]
+[store.logs]
+data = ["data.logs"]
+
+[backup.snapshot]
+target = "store.snapshots"
+frequency = "daily"
+offsite = false
+[backup.offsite]
+target = "store.vault"
+offsite = true
+[backup.verified]
+target = "store.vault"
+offsite = true
+verified = "2024-06-01"
+"#,
+        )
+        .unwrap();
+        let messages = |rule: Box<dyn Rule>| -> Vec<String> {
+            evaluate_rules(&model, &[rule])
+                .into_iter()
+                .map(|f| f.message)
+                .collect()
+        };
+        assert_eq!(
+            messages(Box::new(UnverifiedOffsiteBackup)),
+            vec![
+                "`store.db` holds regulated `data.record`, but `backup.snapshot` is not offsite",
+                "`store.archive` holds regulated `data.record`, but no restore from `backup.offsite` has been verified",
+                "`store.unbacked` holds regulated `data.record`, but has no backup",
+            ]
+        );
+        assert_eq!(
+            messages(Box::new(BackupOnSameServer)),
+            vec!["`store.db` is backed up by `backup.snapshot` to `store.snapshots`, both on `server.db`"]
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use crate::reach::hosts_of;
+use crate::reference::kind_of;
+use crate::{
+    AuthenticationID, BoundaryID, ChannelID, ClassificationID, EncryptionID, EntityRef, FlowID,
+    Model, Relation,
+};
+use indexmap::IndexSet;
+use serde::Serialize;
+
+// A flow between a source and destination in different trust boundaries,
+// and what protects it on the way.
+#[derive(Serialize, Debug, Clone)]
+pub struct BoundaryCrossing {
+    pub flow: FlowID,
+    pub source: EntityRef,
+    pub destination: EntityRef,
+    // the boundaries the flow leaves, then those it enters
+    pub boundaries: Vec<BoundaryID>,
+    pub channel: Option<ChannelID>,
+    // those of the channel, or failing that of a channel it is carried over
+    pub authentication: Option<AuthenticationID>,
+    pub encryption: Option<EncryptionID>,
+    pub classification: Option<ClassificationID>,
+}
+
+// Every flow crossing a boundary, once for each source and destination it
+// crosses between, in model order.
+pub fn boundary_crossings(model: &Model) -> Vec<BoundaryCrossing> {
+    let graph = model.graph();
+    let mut crossings = Vec::new();
+    for (name, flow) in model.flow.iter().flatten() {
+        let id = FlowID::named(name);
+        for source in graph.reverse_neighbors(&id, Relation::FlowsTo) {
+            let from = boundaries_of(model, source);
+            for destination in graph.neighbors(&id, Relation::FlowsTo) {
+                let to = boundaries_of(model, destination);
+                let boundaries: Vec<BoundaryID> = from
+                    .difference(&to)
+                    .chain(to.difference(&from))
+                    .filter_map(|b| b.parse().ok())
+                    .collect();
+                if boundaries.is_empty() {
+                    continue;
+                }
+                let channel = flow.channel.clone();
+                let (authentication, encryption) = match &channel {
+                    Some(c) => protection(model, c),
+                    None => (None, None),
+                };
+                crossings.push(BoundaryCrossing {
+                    flow: id.clone(),
+                    source: source.clone(),
+                    destination: destination.clone(),
+                    boundaries,
+                    channel,
+                    authentication,
+                    encryption,
+                    classification: model.effective_classification(&id),
+                });
+            }
+        }
+    }
+    crossings
+}
+
+// The boundaries an entity is within: those listing it, or failing that,
+// those of the hosts it runs on, or of the networks a host is on.
+pub fn boundaries_of<'a>(model: &'a Model, id: &'a str) -> IndexSet<&'a str> {
+    let graph = model.graph();
+    let listed: IndexSet<&str> = graph
+        .reverse_neighbors(id, Relation::Encloses)
+        .into_iter()
+        .map(EntityRef::as_str)
+        .collect();
+    if !listed.is_empty() {
+        return listed;
+    }
+    match kind_of(id) {
+        "server" | "endpoint" => graph
+            .neighbors(id, Relation::ConnectsTo)
+            .into_iter()
+            .flat_map(|n| boundaries_of(model, n))
+            .collect(),
+        "application" | "store" | "agent" => hosts_of(model, id)
+            .into_iter()
+            .flat_map(|h| boundaries_of(model, h))
+            .collect(),
+        _ => IndexSet::new(),
+    }
+}
+
+// the authentication and encryption of a channel, or failing that, of the
+// nearest channel it is carried over which has them
+fn protection(
+    model: &Model,
+    channel: &ChannelID,
+) -> (Option<AuthenticationID>, Option<EncryptionID>) {
+    let stack = model.channel_stack(channel);
+    (
+        stack.iter().find_map(|c| c.authentication.clone()),
+        stack.iter().find_map(|c| c.encryption.clone()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crossings() {
+        let model: Model = toml::from_str(
+            r#"
+[network.internet]
+[network.dmz]
+[network.prod]
+
+[boundary.untrusted]
+trust = 0
+networks = ["network.internet"]
+[boundary.perimeter]
+trust = 1
+networks = ["network.dmz"]
+[boundary.core]
+trust = 2
+networks = ["network.prod"]
+applications = ["application.admin"]
+
+[endpoint.browser]
+interfaces = [{ network = "network.internet" }]
+[server.web]
+interfaces = [{ network = "network.dmz" }]
+applications = ["application.site", "application.admin"]
+[server.db]
+interfaces = [{ network = "network.prod" }]
+stores = ["store.db"]
+
+[application.site]
+[application.admin]
+[store.db]
+
+[encryption.tls]
+[authentication.password]
+[channel.tcp]
+[channel.tls]
+over = ["channel.tcp"]
+encryption = "encryption.tls"
+[channel.https]
+over = ["channel.tls"]
+authentication = "authentication.password"
+
+[classification.secret]
+level = 3
+[data.records]
+classification = "classification.secret"
+
+[flow.browse]
+sources = ["endpoint.browser"]
+destinations = ["application.site"]
+channel = "channel.https"
+[flow.query]
+sources = ["application.site", "application.admin"]
+destinations = ["store.db"]
+channel = "channel.tcp"
+data = ["data.records"]
+"#,
+        )
+        .unwrap();
+        // an application listed in a boundary is within it, wherever it runs
+        assert_eq!(
+            boundaries_of(&model, "application.admin"),
+            IndexSet::from(["boundary.core"])
+        );
+        let crossings = boundary_crossings(&model);
+        let summary: Vec<(&str, &str, Vec<&str>)> = crossings
+            .iter()
+            .map(|c| {
+                let boundaries = c.boundaries.iter().map(EntityRef::as_str).collect();
+                (c.flow.as_str(), c.source.as_str(), boundaries)
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    "flow.browse",
+                    "endpoint.browser",
+                    vec!["boundary.untrusted", "boundary.perimeter"]
+                ),
+                (
+                    "flow.query",
+                    "application.site",
+                    vec!["boundary.perimeter", "boundary.core"]
+                ),
+            ],
+            summary
+        );
+        assert_eq!(
+            crossings[0].authentication.as_ref().map(EntityRef::as_str),
+            Some("authentication.password")
+        );
+        assert_eq!(
+            crossings[0].encryption.as_ref().map(EntityRef::as_str),
+            Some("encryption.tls")
+        );
+        assert!(crossings[1].encryption.is_none());
+        assert_eq!(
+            crossings[1].classification.as_ref().map(EntityRef::as_str),
+            Some("classification.secret")
+        );
+    }
+}
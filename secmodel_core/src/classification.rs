@@ -0,0 +1,146 @@
+use crate::reference::kind_of;
+use crate::{ClassificationID, DataID, EntityRef, Model, Relation};
+
+impl Model {
+    // the rank of a classification, higher being more restrictive; those
+    // without a level, or missing from the model, rank lowest
+    pub fn classification_level(&self, id: &ClassificationID) -> u32 {
+        id.resolve(self).and_then(|c| c.level).unwrap_or_default()
+    }
+
+    // The most restrictive classification of what an entity holds:
+    //
+    // * data: its own classification, and that of all the data it contains
+    // * store: the data it holds, or holds for the stores it backs
+    // * flow: the data it carries
+    // * server or endpoint: its stores, and the flows to or from it or
+    //   anything it hosts
+    pub fn effective_classification(&self, id: &str) -> Option<ClassificationID> {
+        let graph = self.graph();
+        match kind_of(id) {
+            "data" => self.most_restrictive(
+                std::iter::once(id)
+                    .chain(
+                        graph
+                            .reachable(id, &[Relation::Contains])
+                            .into_iter()
+                            .map(EntityRef::as_str),
+                    )
+                    .filter_map(|d| self.data_by_id(d)?.classification.clone()),
+            ),
+            "store" => {
+                let backed = graph.reverse_reachable(id, &[Relation::BackedBy]);
+                let stores = std::iter::once(id).chain(backed.into_iter().map(EntityRef::as_str));
+                self.most_restrictive(stores.flat_map(|s| {
+                    graph
+                        .neighbors(s, Relation::StoresData)
+                        .into_iter()
+                        .filter_map(|d| self.effective_classification(d))
+                }))
+            }
+            "flow" => self.most_restrictive(
+                graph
+                    .neighbors(id, Relation::CarriesData)
+                    .into_iter()
+                    .filter_map(|d| self.effective_classification(d)),
+            ),
+            "server" | "endpoint" => {
+                // those it lists, and those deployments put on it
+                let hosted = graph.hosted(id);
+                let mut found: Vec<ClassificationID> = Vec::new();
+                for e in std::iter::once(id).chain(hosted.into_iter().map(EntityRef::as_str)) {
+                    if e.starts_with("store.") {
+                        found.extend(self.effective_classification(e));
+                    }
+                    found.extend(
+                        self.entity_flows(e)
+                            .iter()
+                            .filter_map(|f| self.effective_classification(f)),
+                    );
+                }
+                self.most_restrictive(found.into_iter())
+            }
+            _ => None,
+        }
+    }
+
+    // the highest classification of the data a data type contains, not
+    // counting its own
+    pub fn contents_classification(&self, id: &DataID) -> Option<ClassificationID> {
+        let graph = self.graph();
+        self.most_restrictive(
+            graph
+                .reachable(id, &[Relation::Contains])
+                .into_iter()
+                .filter_map(|d| self.data_by_id(d)?.classification.clone()),
+        )
+    }
+
+    // the first of the most restrictive classifications
+    fn most_restrictive(
+        &self,
+        ids: impl Iterator<Item = ClassificationID>,
+    ) -> Option<ClassificationID> {
+        ids.fold(None, |max: Option<ClassificationID>, id| match max {
+            Some(m) if self.classification_level(&m) >= self.classification_level(&id) => Some(m),
+            _ => Some(id),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Model;
+
+    #[test]
+    fn propagation() {
+        let model: Model = toml::from_str(
+            r#"
+[classification.public]
+level = 0
+[classification.secret]
+level = 2
+[classification.internal]
+level = 1
+
+[data.record]
+classification = "classification.internal"
+contains = ["data.ssn", "data.name"]
+[data.ssn]
+classification = "classification.secret"
+[data.name]
+classification = "classification.public"
+
+[store.db]
+data = ["data.record"]
+backing = "store.disk"
+[store.disk]
+
+[server.web]
+stores = ["store.disk"]
+[flow.lookup]
+data = ["data.name"]
+destinations = ["endpoint.laptop"]
+[endpoint.laptop]
+"#,
+        )
+        .unwrap();
+        let effective = |id| model.effective_classification(id).map(|c| c.to_string());
+        let secret = Some("classification.secret".to_string());
+        assert_eq!(secret, effective("data.record"));
+        assert_eq!(secret, effective("data.ssn"));
+        assert_eq!(
+            Some("classification.public".to_string()),
+            effective("data.name")
+        );
+        assert_eq!(secret, effective("store.db"));
+        // a backing store holds the data of the stores it backs
+        assert_eq!(secret, effective("store.disk"));
+        assert_eq!(secret, effective("server.web"));
+        assert_eq!(
+            Some("classification.public".to_string()),
+            effective("endpoint.laptop")
+        );
+        assert_eq!(None, effective("risk.none"));
+    }
+}
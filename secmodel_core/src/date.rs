@@ -0,0 +1,29 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer};
+
+// Dates may be written as TOML dates, `granted = 2024-06-01`, or as
+// strings, `granted = "2024-06-01"`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DateValue {
+    Toml(toml::value::Datetime),
+    Text(String),
+}
+
+pub(crate) fn optional<'de, D: Deserializer<'de>>(d: D) -> Result<Option<NaiveDate>, D::Error> {
+    let Some(value) = Option::<DateValue>::deserialize(d)? else {
+        return Ok(None);
+    };
+    let text = match value {
+        DateValue::Toml(dt) if dt.time.is_none() && dt.offset.is_none() => dt.to_string(),
+        DateValue::Toml(dt) => {
+            return Err(serde::de::Error::custom(format!(
+                "expected a date without a time, found `{dt}`"
+            )))
+        }
+        DateValue::Text(text) => text,
+    };
+    NaiveDate::parse_from_str(&text, "%Y-%m-%d")
+        .map(Some)
+        .map_err(|e| serde::de::Error::custom(format!("invalid date `{text}`: {e}")))
+}
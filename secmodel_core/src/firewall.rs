@@ -0,0 +1,266 @@
+use crate::reach::{crossing, hosts_of, route, Traffic};
+use crate::rules::{Finding, Rule};
+use crate::{EntityRef, FlowControl, FlowID, Model, NetworkID, Relation, Route, Severity};
+
+// Flows which some route joins the ends of, but which the network controls
+// along every route deny.
+pub struct FlowNotAllowed;
+
+impl Rule for FlowNotAllowed {
+    fn name(&self) -> &'static str {
+        "flow-not-allowed"
+    }
+
+    fn evaluate(&self, model: &Model) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for (name, _) in model.flow.iter().flatten() {
+            let id = FlowID::named(name);
+            for (from, to) in ends(model, &id) {
+                let mut traffic = Traffic {
+                    from,
+                    to: Some(to),
+                    flow: Some(id.as_str()),
+                    enforce: false,
+                };
+                let Some(open) = route(model, &traffic) else {
+                    continue;
+                };
+                traffic.enforce = true;
+                if route(model, &traffic).is_some() {
+                    continue;
+                }
+                // name the first crossing on the open route which is denied
+                let Some((out_of, hop)) = open.crossings().find(|(out_of, hop)| {
+                    let via = hop.via.as_ref().map(EntityRef::as_str);
+                    crossing(model, &traffic, out_of, &hop.network, via).is_none()
+                }) else {
+                    continue;
+                };
+                findings.push(Finding {
+                    rule: self.name().to_string(),
+                    severity: Severity::Warning,
+                    message: format!(
+                        "`{id}` from `{from}` to `{to}` is not allowed from `{out_of}` into `{}`",
+                        hop.network
+                    ),
+                    entities: vec![id.untyped(), out_of.untyped(), hop.network.untyped()],
+                    location: model.sources.locate(id.as_str()),
+                });
+            }
+        }
+        findings
+    }
+}
+
+// Network control rules naming flows which never cross into, or out of,
+// the network they control.
+pub struct UnusedFlowControl;
+
+impl Rule for UnusedFlowControl {
+    fn name(&self) -> &'static str {
+        "unused-flow-control"
+    }
+
+    fn evaluate(&self, model: &Model) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for (network, field, n, rule) in flow_controls(model) {
+            for flow in rule.flows.iter().flatten() {
+                if flow.resolve(model).is_none() {
+                    continue;
+                }
+                let routes = flow_routes(model, flow);
+                let used = routes.iter().any(|r| {
+                    r.crossings().any(|(out_of, hop)| {
+                        if field == "ingress" {
+                            hop.network == network
+                        } else {
+                            *out_of == network
+                        }
+                    })
+                });
+                if used {
+                    continue;
+                }
+                let direction = if field == "ingress" {
+                    "enters"
+                } else {
+                    "leaves"
+                };
+                findings.push(Finding {
+                    rule: self.name().to_string(),
+                    severity: Severity::Warning,
+                    message: format!(
+                        "{field} rule {n} names `{flow}`, which never {direction} `{network}`"
+                    ),
+                    entities: vec![network.untyped(), flow.untyped()],
+                    location: model
+                        .sources
+                        .locate(&format!("{network}.controls.{field}[{n}].flows")),
+                });
+            }
+        }
+        findings
+    }
+}
+
+// Network control rules whose controller is not a server with interfaces
+// on the network it controls and on another network, and so cannot be
+// where traffic crosses between them.
+pub struct DetachedFlowController;
+
+impl Rule for DetachedFlowController {
+    fn name(&self) -> &'static str {
+        "detached-flow-controller"
+    }
+
+    fn evaluate(&self, model: &Model) -> Vec<Finding> {
+        let graph = model.graph();
+        let mut findings = Vec::new();
+        for (network, field, n, rule) in flow_controls(model) {
+            let Some(controller) = &rule.controller else {
+                continue;
+            };
+            if controller.resolve(model).is_none() {
+                continue;
+            }
+            let networks = graph.neighbors(controller, Relation::ConnectsTo);
+            let on = networks.iter().any(|e| e.as_str() == network.as_str());
+            let beyond = networks.iter().any(|e| e.as_str() != network.as_str());
+            let message = match (on, beyond) {
+                (true, true) => continue,
+                (false, _) => format!("{field} rule {n} controller `{controller}` is not on `{network}`"),
+                (true, false) => format!(
+                    "{field} rule {n} controller `{controller}` is not on any network other than `{network}`"
+                ),
+            };
+            findings.push(Finding {
+                rule: self.name().to_string(),
+                severity: Severity::Error,
+                message,
+                entities: vec![network.untyped(), controller.untyped()],
+                location: model
+                    .sources
+                    .locate(&format!("{network}.controls.{field}[{n}].controller")),
+            });
+        }
+        findings
+    }
+}
+
+// every ingress and egress rule of every network, with its position
+fn flow_controls(model: &Model) -> Vec<(NetworkID, &'static str, usize, &FlowControl)> {
+    let mut rules = Vec::new();
+    for (name, network) in model.network.iter().flatten() {
+        let Some(controls) = &network.controls else {
+            continue;
+        };
+        for (field, list) in [("ingress", &controls.ingress), ("egress", &controls.egress)] {
+            for (n, rule) in list.iter().flatten().enumerate() {
+                rules.push((NetworkID::named(name), field, n, rule));
+            }
+        }
+    }
+    rules
+}
+
+// the hosts at either end of a flow, each source paired with each
+// destination
+fn ends<'a>(model: &'a Model, flow: &'a FlowID) -> Vec<(&'a str, &'a str)> {
+    let graph = model.graph();
+    let sources = graph.reverse_neighbors(flow, Relation::FlowsTo);
+    let destinations = graph.neighbors(flow, Relation::FlowsTo);
+    let mut pairs = Vec::new();
+    for from in sources.into_iter().flat_map(|s| hosts_of(model, s)) {
+        for to in destinations.iter().flat_map(|d| hosts_of(model, d)) {
+            if from != to && !pairs.contains(&(from, to)) {
+                pairs.push((from, to));
+            }
+        }
+    }
+    pairs
+}
+
+// the routes a flow's traffic takes, whether or not the network controls
+// let it through
+pub(crate) fn flow_routes(model: &Model, flow: &FlowID) -> Vec<Route> {
+    let mut routes = Vec::new();
+    for (from, to) in ends(model, flow) {
+        for enforce in [false, true] {
+            let traffic = Traffic {
+                from,
+                to: Some(to),
+                flow: Some(flow.as_str()),
+                enforce,
+            };
+            routes.extend(route(model, &traffic));
+        }
+    }
+    routes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluate_rules;
+
+    #[test]
+    fn firewall() {
+        let model: Model = toml::from_str(
+            r#"
+[network.office]
+[network.dmz]
+[network.prod]
+controls = { ingress_default = "deny", ingress = [
+    { flows = ["flow.web"], controller = "server.fw", action = "allow" },
+    { flows = ["flow.backup"], controller = "server.fw", action = "allow" },
+    { flows = ["flow.web"], controller = "server.web", action = "allow" },
+] }
+
+[server.fw]
+interfaces = [{ network = "network.office" }, { network = "network.dmz" }, { network = "network.prod" }]
+[server.web]
+interfaces = [{ network = "network.prod" }]
+applications = ["application.site"]
+[server.db]
+interfaces = [{ network = "network.prod" }]
+[server.backup]
+interfaces = [{ network = "network.dmz" }]
+[endpoint.desk]
+interfaces = [{ network = "network.office" }]
+
+[application.site]
+
+[flow.web]
+sources = ["endpoint.desk"]
+destinations = ["application.site"]
+[flow.ssh]
+sources = ["endpoint.desk"]
+destinations = ["server.db"]
+[flow.backup]
+sources = ["server.db"]
+destinations = ["server.backup"]
+"#,
+        )
+        .unwrap();
+
+        let messages = |rule: Box<dyn Rule>| -> Vec<String> {
+            evaluate_rules(&model, &[rule])
+                .into_iter()
+                .map(|f| f.message)
+                .collect()
+        };
+        assert_eq!(
+            messages(Box::new(FlowNotAllowed)),
+            vec!["`flow.ssh` from `endpoint.desk` to `server.db` is not allowed from `network.office` into `network.prod`"]
+        );
+        // the backup flow leaves the network, so its ingress rule is unused
+        assert_eq!(
+            messages(Box::new(UnusedFlowControl)),
+            vec!["ingress rule 1 names `flow.backup`, which never enters `network.prod`"]
+        );
+        assert_eq!(
+            messages(Box::new(DetachedFlowController)),
+            vec!["ingress rule 2 controller `server.web` is not on any network other than `network.prod`"]
+        );
+    }
+}
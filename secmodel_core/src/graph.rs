@@ -0,0 +1,581 @@
+use crate::{
+    Acceptance, Actor, Agent, Application, Authentication, Backup, Boundary, Channel, Control,
+    Custody, Data, Deployment, Encryption, Endpoint, EntityRef, Flow, Key, Lookup, Model, Network,
+    NetworkInterface, Process, Protocol, RiskID, Secret, Server, Store, Threat,
+};
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+// The kinds of relationship between entities, each read from the entity an
+// edge starts at, e.g. `server.prod` hosts `application.mainapp`.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum Relation {
+    // a server or endpoint runs an application, store or agent
+    Hosts,
+    // a deployment puts an application or store on to its targets
+    Deploys,
+    // a deployment targets a server, or a backup copies to a store
+    Targets,
+    // a server, endpoint or network has an interface on a network, or a
+    // threat acts from a network
+    ConnectsTo,
+    // a network is carried over another network, e.g. a VPN
+    HostedOn,
+    // a source sends a flow, and a flow reaches its destination
+    FlowsTo,
+    // a flow carries data
+    CarriesData,
+    // a store holds data
+    StoresData,
+    // data contains other data
+    Contains,
+    // a store is backed by another store
+    BackedBy,
+    // a store is backed up by a backup
+    BackedUpBy,
+    // a flow, channel, network or agent relies on a channel, protocol,
+    // process or security mechanism, or a security mechanism on a key or
+    // secret
+    Uses,
+    // data has a classification
+    ClassifiedAs,
+    // data is subject to a regulation
+    RegulatedBy,
+    // data describes a subject
+    Describes,
+    // a store or server holds a key or secret
+    Holds,
+    // an entity is protected by a control
+    ControlledBy,
+    // a control mitigates a risk
+    Mitigates,
+    // a control remediates a risk
+    Remediates,
+    // an entity is exposed to a risk
+    ExposesRisk,
+    // an entity is owned by an actor
+    OwnedBy,
+    // data is looked after by a steward
+    StewardedBy,
+    // an agent or threat acts on behalf of an actor
+    ActsFor,
+    // a boundary encloses networks, servers, endpoints or applications
+    Encloses,
+    // an acceptance accepts a risk, on the entities it applies to, as
+    // approved by an actor
+    Accepts,
+    AppliesTo,
+    ApprovedBy,
+    // a comment is about an entity
+    Annotates,
+}
+
+impl Relation {
+    pub const ALL: [Relation; 28] = [
+        Relation::Hosts,
+        Relation::Deploys,
+        Relation::Targets,
+        Relation::ConnectsTo,
+        Relation::HostedOn,
+        Relation::FlowsTo,
+        Relation::CarriesData,
+        Relation::StoresData,
+        Relation::Contains,
+        Relation::BackedBy,
+        Relation::BackedUpBy,
+        Relation::Uses,
+        Relation::Holds,
+        Relation::ClassifiedAs,
+        Relation::RegulatedBy,
+        Relation::Describes,
+        Relation::ControlledBy,
+        Relation::Mitigates,
+        Relation::Remediates,
+        Relation::ExposesRisk,
+        Relation::OwnedBy,
+        Relation::StewardedBy,
+        Relation::ActsFor,
+        Relation::Encloses,
+        Relation::Accepts,
+        Relation::AppliesTo,
+        Relation::ApprovedBy,
+        Relation::Annotates,
+    ];
+}
+
+impl FromStr for Relation {
+    type Err = String;
+
+    // the inverse of `Display`, e.g. `stores-data`
+    fn from_str(name: &str) -> Result<Relation, String> {
+        Relation::ALL
+            .into_iter()
+            .find(|r| r.to_string() == name)
+            .ok_or_else(|| format!("unknown relation `{name}`"))
+    }
+}
+
+impl fmt::Display for Relation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Relation::Hosts => "hosts",
+            Relation::Deploys => "deploys",
+            Relation::Targets => "targets",
+            Relation::ConnectsTo => "connects-to",
+            Relation::HostedOn => "hosted-on",
+            Relation::FlowsTo => "flows-to",
+            Relation::CarriesData => "carries-data",
+            Relation::StoresData => "stores-data",
+            Relation::Contains => "contains",
+            Relation::BackedBy => "backed-by",
+            Relation::BackedUpBy => "backed-up-by",
+            Relation::Uses => "uses",
+            Relation::Holds => "holds",
+            Relation::ClassifiedAs => "classified-as",
+            Relation::RegulatedBy => "regulated-by",
+            Relation::Describes => "describes",
+            Relation::ControlledBy => "controlled-by",
+            Relation::Mitigates => "mitigates",
+            Relation::Remediates => "remediates",
+            Relation::ExposesRisk => "exposes-risk",
+            Relation::OwnedBy => "owned-by",
+            Relation::StewardedBy => "stewarded-by",
+            Relation::ActsFor => "acts-for",
+            Relation::Encloses => "encloses",
+            Relation::Accepts => "accepts",
+            Relation::AppliesTo => "applies-to",
+            Relation::ApprovedBy => "approved-by",
+            Relation::Annotates => "annotates",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Edge {
+    pub from: EntityRef,
+    pub relation: Relation,
+    pub to: EntityRef,
+}
+
+// An index of every relationship in a model, built once so that queries do
+// not have to re-scan each table of the model.  Edges are kept in the order
+// the model defines them.  References to entities missing from the model
+// are kept as edges too; `validate` reports those.
+#[derive(Default)]
+pub struct ModelGraph {
+    edges: Vec<Edge>,
+    outgoing: HashMap<String, Vec<usize>>,
+    incoming: HashMap<String, Vec<usize>>,
+}
+
+impl ModelGraph {
+    #[allow(clippy::too_many_lines)]
+    pub fn new(model: &Model) -> ModelGraph {
+        let mut g = ModelGraph::default();
+
+        for (id, server) in entities::<Server>(model) {
+            g.interfaces(&id, server.interfaces.as_ref());
+            g.all(&id, Relation::Hosts, server.applications.as_ref());
+            g.all(&id, Relation::Hosts, server.stores.as_ref());
+            g.one(&id, Relation::OwnedBy, server.owner.as_ref());
+            g.risks(&id, server.risks.as_ref());
+        }
+        for (id, endpoint) in entities::<Endpoint>(model) {
+            g.interfaces(&id, endpoint.interfaces.as_ref());
+            g.all(&id, Relation::Hosts, endpoint.applications.as_ref());
+            g.all(&id, Relation::Hosts, endpoint.agents.as_ref());
+            g.all(&id, Relation::Hosts, endpoint.stores.as_ref());
+            g.one(&id, Relation::OwnedBy, endpoint.owner.as_ref());
+            g.risks(&id, endpoint.risks.as_ref());
+        }
+        for (id, network) in entities::<Network>(model) {
+            g.interfaces(&id, network.interfaces.as_ref());
+            g.all(&id, Relation::HostedOn, network.host_networks.as_ref());
+            g.all(&id, Relation::Uses, network.protocols.as_ref());
+            g.risks(&id, network.risks.as_ref());
+        }
+        for (id, deployment) in entities::<Deployment>(model) {
+            g.all(&id, Relation::Targets, deployment.targets.as_ref());
+            g.all(&id, Relation::Deploys, deployment.applications.as_ref());
+            g.all(&id, Relation::Deploys, deployment.stores.as_ref());
+            g.risks(&id, deployment.risks.as_ref());
+        }
+        for (id, agent) in entities::<Agent>(model) {
+            // an agent names the host it runs on, rather than the reverse
+            if let Some(endpoint) = &agent.endpoint {
+                g.link(endpoint, Relation::Hosts, &id);
+            }
+            if let Some(server) = &agent.server {
+                g.link(server, Relation::Hosts, &id);
+            }
+            g.one(&id, Relation::ActsFor, agent.actor.as_ref());
+            g.one(&id, Relation::Uses, agent.process.as_ref());
+            g.risks(&id, agent.risks.as_ref());
+        }
+        for (id, application) in entities::<Application>(model) {
+            g.all(&id, Relation::ControlledBy, application.controls.as_ref());
+            g.risks(&id, application.risks.as_ref());
+        }
+        for (id, store) in entities::<Store>(model) {
+            g.all(&id, Relation::StoresData, store.data.as_ref());
+            g.one(&id, Relation::BackedBy, store.backing.as_ref());
+            g.one(&id, Relation::BackedUpBy, store.backup.as_ref());
+            g.one(&id, Relation::Uses, store.encryption.as_ref());
+            g.one(&id, Relation::Uses, store.authentication.as_ref());
+            g.one(&id, Relation::Uses, store.authorization.as_ref());
+            g.all(&id, Relation::ControlledBy, store.controls.as_ref());
+            g.risks(&id, store.risks.as_ref());
+        }
+        for (id, data) in entities::<Data>(model) {
+            g.all(&id, Relation::Contains, data.contains.as_ref());
+            g.one(&id, Relation::ClassifiedAs, data.classification.as_ref());
+            g.all(&id, Relation::RegulatedBy, data.regulations.as_ref());
+            g.all(&id, Relation::Describes, data.subjects.as_ref());
+            g.one(&id, Relation::OwnedBy, data.owner.as_ref());
+            g.one(&id, Relation::StewardedBy, data.steward.as_ref());
+            g.all(&id, Relation::ControlledBy, data.controls.as_ref());
+            g.risks(&id, data.risks.as_ref());
+        }
+        for (id, flow) in entities::<Flow>(model) {
+            for source in flow.sources.iter().flatten() {
+                g.link(source, Relation::FlowsTo, &id);
+            }
+            g.all(&id, Relation::FlowsTo, flow.destinations.as_ref());
+            g.all(&id, Relation::CarriesData, flow.data.as_ref());
+            g.one(&id, Relation::Uses, flow.channel.as_ref());
+            g.risks(&id, flow.risks.as_ref());
+        }
+        for (id, channel) in entities::<Channel>(model) {
+            g.all(&id, Relation::Uses, channel.over.as_ref());
+            g.all(&id, Relation::Uses, channel.protocols.as_ref());
+            g.one(&id, Relation::Uses, channel.encryption.as_ref());
+            g.one(&id, Relation::Uses, channel.authentication.as_ref());
+            g.one(&id, Relation::Uses, channel.authorization.as_ref());
+            g.all(&id, Relation::ControlledBy, channel.controls.as_ref());
+            g.risks(&id, channel.risks.as_ref());
+        }
+        for (id, backup) in entities::<Backup>(model) {
+            g.one(&id, Relation::Targets, backup.target.as_ref());
+            g.one(&id, Relation::Uses, backup.encryption.as_ref());
+            g.risks(&id, backup.risks.as_ref());
+        }
+        for (id, authentication) in entities::<Authentication>(model) {
+            g.all(&id, Relation::Uses, authentication.keys.as_ref());
+            g.all(&id, Relation::Uses, authentication.secrets.as_ref());
+        }
+        for (id, encryption) in entities::<Encryption>(model) {
+            g.all(&id, Relation::Uses, encryption.keys.as_ref());
+        }
+        for (id, key) in entities::<Key>(model) {
+            g.custody(&id, &key.custody);
+            g.risks(&id, key.risks.as_ref());
+        }
+        for (id, secret) in entities::<Secret>(model) {
+            g.custody(&id, &secret.custody);
+            g.risks(&id, secret.risks.as_ref());
+        }
+        for (id, control) in entities::<Control>(model) {
+            g.all(&id, Relation::Mitigates, control.mitigates.as_ref());
+            g.all(&id, Relation::Remediates, control.remediates.as_ref());
+            g.risks(&id, control.risks.as_ref());
+        }
+        for (id, boundary) in entities::<Boundary>(model) {
+            g.all(&id, Relation::Encloses, boundary.networks.as_ref());
+            g.all(&id, Relation::Encloses, boundary.servers.as_ref());
+            g.all(&id, Relation::Encloses, boundary.endpoints.as_ref());
+            g.all(&id, Relation::Encloses, boundary.applications.as_ref());
+            g.risks(&id, boundary.risks.as_ref());
+        }
+        for (id, acceptance) in entities::<Acceptance>(model) {
+            g.one(&id, Relation::Accepts, acceptance.risk.as_ref());
+            g.all(&id, Relation::AppliesTo, acceptance.entities.as_ref());
+            g.one(&id, Relation::ApprovedBy, acceptance.approver.as_ref());
+        }
+        for (id, threat) in entities::<Threat>(model) {
+            g.all(&id, Relation::ActsFor, threat.actor.as_ref());
+            g.all(&id, Relation::ConnectsTo, threat.networks.as_ref());
+            g.risks(&id, threat.risk.as_ref());
+        }
+        for (id, actor) in entities::<Actor>(model) {
+            g.risks(&id, actor.risks.as_ref());
+        }
+        for (id, process) in entities::<Process>(model) {
+            g.risks(&id, process.risks.as_ref());
+        }
+        for (id, protocol) in entities::<Protocol>(model) {
+            g.risks(&id, protocol.risks.as_ref());
+        }
+        for (n, comment) in model.comment.iter().flatten().enumerate() {
+            if let Ok(id) = format!("comment.{n}").parse::<EntityRef>() {
+                g.link(&id, Relation::Annotates, &comment.entity);
+            }
+        }
+        g
+    }
+
+    #[must_use]
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    // the edges starting at an entity
+    pub fn outgoing(&self, id: &str) -> impl Iterator<Item = &Edge> {
+        self.indexed(&self.outgoing, id)
+    }
+
+    // the edges ending at an entity
+    pub fn incoming(&self, id: &str) -> impl Iterator<Item = &Edge> {
+        self.indexed(&self.incoming, id)
+    }
+
+    // the entities `id` is related to, e.g. the applications a server hosts
+    #[must_use]
+    pub fn neighbors(&self, id: &str, relation: Relation) -> Vec<&EntityRef> {
+        self.outgoing(id)
+            .filter(|e| e.relation == relation)
+            .map(|e| &e.to)
+            .collect()
+    }
+
+    // the entities related to `id`, e.g. the servers hosting an application
+    #[must_use]
+    pub fn reverse_neighbors(&self, id: &str, relation: Relation) -> Vec<&EntityRef> {
+        self.incoming(id)
+            .filter(|e| e.relation == relation)
+            .map(|e| &e.from)
+            .collect()
+    }
+
+    // the servers and endpoints running an entity: those listing it, then
+    // those deployments put it on
+    #[must_use]
+    pub fn hosts(&self, id: &str) -> Vec<&EntityRef> {
+        let listing = self.reverse_neighbors(id, Relation::Hosts);
+        let deployments = self.reverse_neighbors(id, Relation::Deploys);
+        self.placed(listing, deployments, Relation::Targets)
+    }
+
+    // what a server or endpoint runs: what it lists, then what deployments
+    // put on it
+    #[must_use]
+    pub fn hosted(&self, host: &str) -> Vec<&EntityRef> {
+        let listed = self.neighbors(host, Relation::Hosts);
+        let deployments = self.reverse_neighbors(host, Relation::Targets);
+        self.placed(listed, deployments, Relation::Deploys)
+    }
+
+    // those listed, then those at the other end of the deployments, each once
+    fn placed<'a>(
+        &'a self,
+        listed: Vec<&'a EntityRef>,
+        deployments: Vec<&'a EntityRef>,
+        relation: Relation,
+    ) -> Vec<&'a EntityRef> {
+        let mut placed = listed;
+        let deployed = deployments
+            .into_iter()
+            .filter(|d| d.kind() == "deployment")
+            .flat_map(|d| self.neighbors(d, relation));
+        for id in deployed {
+            if !placed.contains(&id) {
+                placed.push(id);
+            }
+        }
+        placed
+    }
+
+    // every entity reachable from `id` by following edges of the given
+    // relations, in breadth first order, not including `id` itself
+    #[must_use]
+    pub fn reachable(&self, id: &str, relations: &[Relation]) -> Vec<&EntityRef> {
+        let mut seen = HashSet::from([id]);
+        let mut queue = VecDeque::from([id]);
+        let mut found = Vec::new();
+        while let Some(next) = queue.pop_front() {
+            for e in self.outgoing(next) {
+                if relations.contains(&e.relation) && seen.insert(e.to.as_str()) {
+                    queue.push_back(e.to.as_str());
+                    found.push(&e.to);
+                }
+            }
+        }
+        found
+    }
+
+    // every entity from which `id` is reachable by following edges of the
+    // given relations, in breadth first order, not including `id` itself
+    #[must_use]
+    pub fn reverse_reachable(&self, id: &str, relations: &[Relation]) -> Vec<&EntityRef> {
+        let mut seen = HashSet::from([id]);
+        let mut queue = VecDeque::from([id]);
+        let mut found = Vec::new();
+        while let Some(next) = queue.pop_front() {
+            for e in self.incoming(next) {
+                if relations.contains(&e.relation) && seen.insert(e.from.as_str()) {
+                    queue.push_back(e.from.as_str());
+                    found.push(&e.from);
+                }
+            }
+        }
+        found
+    }
+
+    // the shortest chain of edges leading from `from` to `to`, following
+    // only the given relations, or every relation when `relations` is empty
+    #[must_use]
+    pub fn path(&self, from: &str, to: &str, relations: &[Relation]) -> Option<Vec<&Edge>> {
+        let mut via: HashMap<&str, &Edge> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        while let Some(next) = queue.pop_front() {
+            if next == to {
+                let mut path = Vec::new();
+                let mut at = to;
+                while at != from {
+                    let edge = via[at];
+                    path.push(edge);
+                    at = edge.from.as_str();
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for e in self.outgoing(next) {
+                let wanted = relations.is_empty() || relations.contains(&e.relation);
+                if wanted && e.to != from && !via.contains_key(e.to.as_str()) {
+                    via.insert(e.to.as_str(), e);
+                    queue.push_back(e.to.as_str());
+                }
+            }
+        }
+        None
+    }
+
+    fn indexed<'a>(
+        &'a self,
+        index: &'a HashMap<String, Vec<usize>>,
+        id: &str,
+    ) -> impl Iterator<Item = &'a Edge> {
+        index.get(id).into_iter().flatten().map(|&n| &self.edges[n])
+    }
+
+    fn link<K, L>(&mut self, from: &EntityRef<K>, relation: Relation, to: &EntityRef<L>) {
+        let n = self.edges.len();
+        self.outgoing.entry(from.to_string()).or_default().push(n);
+        self.incoming.entry(to.to_string()).or_default().push(n);
+        self.edges.push(Edge {
+            from: from.untyped(),
+            relation,
+            to: to.untyped(),
+        });
+    }
+
+    fn one<K, L>(&mut self, from: &EntityRef<K>, relation: Relation, to: Option<&EntityRef<L>>) {
+        if let Some(to) = to {
+            self.link(from, relation, to);
+        }
+    }
+
+    fn all<K, L>(
+        &mut self,
+        from: &EntityRef<K>,
+        relation: Relation,
+        to: Option<&Vec<EntityRef<L>>>,
+    ) {
+        for to in to.into_iter().flatten() {
+            self.link(from, relation, to);
+        }
+    }
+
+    fn risks<K>(&mut self, from: &EntityRef<K>, risks: Option<&Vec<RiskID>>) {
+        self.all(from, Relation::ExposesRisk, risks);
+    }
+
+    // where a key or secret is held and who owns it
+    fn custody<K>(&mut self, from: &EntityRef<K>, custody: &Custody) {
+        if let Some(store) = &custody.store {
+            self.link(store, Relation::Holds, from);
+        }
+        if let Some(server) = &custody.server {
+            self.link(server, Relation::Holds, from);
+        }
+        self.one(from, Relation::OwnedBy, custody.owner.as_ref());
+    }
+
+    fn interfaces<K>(&mut self, from: &EntityRef<K>, interfaces: Option<&Vec<NetworkInterface>>) {
+        for i in interfaces.into_iter().flatten() {
+            self.one(from, Relation::ConnectsTo, i.network.as_ref());
+        }
+    }
+}
+
+// the entities in the table for `K`, along with their IDs
+fn entities<'a, K: Lookup + 'a>(
+    model: &'a Model,
+) -> impl Iterator<Item = (EntityRef<K>, &'a K)> + 'a {
+    K::table(model)
+        .into_iter()
+        .flat_map(IndexMap::iter)
+        .map(|(name, entity)| (EntityRef::named(name), entity))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::load;
+
+    #[test]
+    fn relationships() {
+        let model = load("../test/test_model.toml").unwrap();
+        let g = ModelGraph::new(&model);
+
+        assert_eq!(
+            vec!["application.mainapp"],
+            g.neighbors("server.prod", Relation::Hosts)
+                .into_iter()
+                .filter(|id| id.kind() == "application")
+                .map(EntityRef::as_str)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            g.reverse_neighbors("store.prod-fs", Relation::BackedBy),
+            vec!["store.main-mysql"]
+        );
+        assert_eq!(
+            g.reverse_neighbors("network.center", Relation::ConnectsTo),
+            vec!["server.cable-router", "server.prod-fw"]
+        );
+
+        // the client's credentials reach the database through the app
+        let path = g
+            .path(
+                "agent.client-browser",
+                "store.main-mysql",
+                &[Relation::FlowsTo],
+            )
+            .unwrap();
+        let hops: Vec<&str> = path.iter().map(|e| e.to.as_str()).collect();
+        assert_eq!(
+            vec![
+                "flow.mainapp-client",
+                "application.mainapp",
+                "flow.mainapp-backend",
+                "store.main-mysql"
+            ],
+            hops
+        );
+        assert!(g
+            .path(
+                "store.main-mysql",
+                "agent.client-browser",
+                &[Relation::FlowsTo]
+            )
+            .is_none());
+        assert!(g
+            .reachable("agent.client-browser", &[Relation::FlowsTo])
+            .contains(&&EntityRef::parse("store.prod-fs").unwrap()));
+    }
+}
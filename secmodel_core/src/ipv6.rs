@@ -0,0 +1,245 @@
+use crate::firewall::flow_routes;
+use crate::rules::{Finding, Rule};
+use crate::{
+    ChannelID, FlowControl, FlowControlAction, FlowID, Model, Network, NetworkID, Severity,
+};
+use std::collections::HashSet;
+
+// The protocol a network or channel lists to say it carries IPv6.
+pub const IPV6: &str = "protocol.ipv6";
+
+// Networks listing IPv6 among their protocols without saying which IPv6
+// addresses they use, so its traffic cannot be accounted for.
+pub struct Ipv6WithoutRanges;
+
+impl Rule for Ipv6WithoutRanges {
+    fn name(&self) -> &'static str {
+        "ipv6-without-ranges"
+    }
+
+    fn evaluate(&self, model: &Model) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for (name, network) in model.network.iter().flatten() {
+            let id = NetworkID::named(name);
+            let listed = network
+                .protocols
+                .iter()
+                .flatten()
+                .any(|p| p.as_str() == IPV6);
+            if !listed || network.ipv6_ranges.as_ref().is_some_and(|r| !r.is_empty()) {
+                continue;
+            }
+            findings.push(Finding {
+                rule: self.name().to_string(),
+                severity: Severity::Warning,
+                message: format!("`{id}` carries IPv6 but has no `ipv6_ranges`"),
+                entities: vec![id.untyped()],
+                location: model.sources.locate(&format!("{id}.protocols")),
+            });
+        }
+        findings
+    }
+}
+
+// Networks with IPv6 enabled which let any traffic in, having neither
+// ingress rules nor a `deny` ingress default.
+pub struct Ipv6WithoutIngressControls;
+
+impl Rule for Ipv6WithoutIngressControls {
+    fn name(&self) -> &'static str {
+        "ipv6-without-ingress-controls"
+    }
+
+    fn evaluate(&self, model: &Model) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for (name, network) in model.network.iter().flatten() {
+            let id = NetworkID::named(name);
+            if !ipv6_enabled(network) {
+                continue;
+            }
+            let controlled = network.controls.as_ref().is_some_and(|c| {
+                c.ingress.as_ref().is_some_and(|r| !r.is_empty())
+                    || c.ingress_default == Some(FlowControlAction::Deny)
+            });
+            if controlled {
+                continue;
+            }
+            findings.push(Finding {
+                rule: self.name().to_string(),
+                severity: Severity::Warning,
+                message: format!("`{id}` has IPv6 enabled but no ingress controls"),
+                entities: vec![id.untyped()],
+                location: model.sources.locate(id.as_str()),
+            });
+        }
+        findings
+    }
+}
+
+// Channels carrying IPv6 on a network whose controls only describe IPv4:
+// every rule names flows, and none of them is over IPv6, so IPv6 traffic
+// falls through to the defaults.
+pub struct Ipv6UncontrolledChannel;
+
+impl Rule for Ipv6UncontrolledChannel {
+    fn name(&self) -> &'static str {
+        "ipv6-uncontrolled-channel"
+    }
+
+    fn evaluate(&self, model: &Model) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for (name, network) in model.network.iter().flatten() {
+            let id = NetworkID::named(name);
+            let Some(controls) = &network.controls else {
+                continue;
+            };
+            let rules: Vec<&FlowControl> = controls
+                .ingress
+                .iter()
+                .chain(&controls.egress)
+                .flatten()
+                .collect();
+            let ipv4_only = !rules.is_empty()
+                && rules.iter().all(|rule| {
+                    rule.flows.as_ref().is_some_and(|flows| {
+                        !flows
+                            .iter()
+                            .any(|f| flow_channel(model, f).is_some_and(|c| carries_ipv6(model, c)))
+                    })
+                });
+            if !ipv4_only {
+                continue;
+            }
+
+            let mut reported = HashSet::new();
+            for (flow_name, flow) in model.flow.iter().flatten() {
+                let flow_id = FlowID::named(flow_name);
+                let Some(channel) = &flow.channel else {
+                    continue;
+                };
+                if reported.contains(channel) || !carries_ipv6(model, channel) {
+                    continue;
+                }
+                let crosses = flow_routes(model, &flow_id)
+                    .iter()
+                    .any(|r| r.start == id || r.hops.iter().any(|h| h.network == id));
+                if !crosses {
+                    continue;
+                }
+                reported.insert(channel.clone());
+                findings.push(Finding {
+                    rule: self.name().to_string(),
+                    severity: Severity::Warning,
+                    message: format!(
+                        "`{channel}` carries IPv6 on `{id}`, whose controls only describe IPv4"
+                    ),
+                    entities: vec![id.untyped(), channel.untyped(), flow_id.untyped()],
+                    location: model.sources.locate(&format!("{id}.controls")),
+                });
+            }
+        }
+        findings
+    }
+}
+
+fn ipv6_enabled(network: &Network) -> bool {
+    network
+        .protocols
+        .iter()
+        .flatten()
+        .any(|p| p.as_str() == IPV6)
+        || network.ipv6_ranges.as_ref().is_some_and(|r| !r.is_empty())
+}
+
+fn flow_channel<'a>(model: &'a Model, flow: &FlowID) -> Option<&'a ChannelID> {
+    flow.resolve(model)?.channel.as_ref()
+}
+
+// whether a channel, or any channel it is carried over, uses IPv6
+fn carries_ipv6(model: &Model, channel: &ChannelID) -> bool {
+    let stack = model.channel_stack(channel);
+    stack
+        .iter()
+        .any(|c| c.protocols.iter().flatten().any(|p| p.as_str() == IPV6))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluate_rules;
+
+    #[test]
+    fn ipv6() {
+        let model: Model = toml::from_str(
+            r#"
+[protocol.ipv4]
+[protocol.ipv6]
+
+[network.internet]
+protocols = ["protocol.ipv4", "protocol.ipv6"]
+[network.office]
+protocols = ["protocol.ipv6"]
+ipv6_ranges = ["2001:db8::/48"]
+controls = { ingress_default = "deny" }
+[network.prod]
+protocols = ["protocol.ipv4"]
+controls = { ingress = [{ flows = ["flow.admin"] }] }
+
+[server.fw]
+interfaces = [{ network = "network.internet" }, { network = "network.prod" }]
+[server.web]
+interfaces = [{ network = "network.prod" }]
+[endpoint.admin]
+interfaces = [{ network = "network.internet" }]
+
+[channel.ipv4]
+protocols = ["protocol.ipv4"]
+[channel.ip]
+protocols = ["protocol.ipv4", "protocol.ipv6"]
+[channel.https]
+over = ["channel.ip"]
+[channel.ssh]
+over = ["channel.ipv4"]
+
+[flow.admin]
+sources = ["endpoint.admin"]
+destinations = ["server.web"]
+channel = "channel.ssh"
+[flow.web]
+sources = ["endpoint.admin"]
+destinations = ["server.web"]
+channel = "channel.https"
+"#,
+        )
+        .unwrap();
+
+        let findings = |rule: Box<dyn Rule>| -> Vec<(String, String)> {
+            evaluate_rules(&model, &[rule])
+                .into_iter()
+                .map(|f| (f.entities[0].to_string(), f.message))
+                .collect()
+        };
+        assert_eq!(
+            findings(Box::new(Ipv6WithoutRanges)),
+            vec![(
+                "network.internet".to_string(),
+                "`network.internet` carries IPv6 but has no `ipv6_ranges`".to_string()
+            )]
+        );
+        assert_eq!(
+            findings(Box::new(Ipv6WithoutIngressControls)),
+            vec![(
+                "network.internet".to_string(),
+                "`network.internet` has IPv6 enabled but no ingress controls".to_string()
+            )]
+        );
+        assert_eq!(
+            findings(Box::new(Ipv6UncontrolledChannel)),
+            vec![(
+                "network.prod".to_string(),
+                "`channel.https` carries IPv6 on `network.prod`, whose controls only describe IPv4"
+                    .to_string()
+            )]
+        );
+    }
+}
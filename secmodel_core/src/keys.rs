@@ -0,0 +1,237 @@
+use crate::reach::hosts_of;
+use crate::rules::{Finding, Rule};
+use crate::{EntityRef, Model, Relation, Severity};
+use indexmap::IndexSet;
+
+// Keys and secrets held in a store they protect or in a store holding data
+// they protect, or on a server or endpoint hosting such a store, so that
+// whoever gets the data gets the key to it too.  A key or secret protects
+// the stores and channels whose encryption or authentication names it, the
+// data in those stores, and the data of the flows over those channels.
+pub struct KeyWithData;
+
+impl Rule for KeyWithData {
+    fn name(&self) -> &'static str {
+        "key-with-data"
+    }
+
+    fn evaluate(&self, model: &Model) -> Vec<Finding> {
+        let graph = model.graph();
+        let ids = model
+            .key
+            .iter()
+            .flatten()
+            .map(|(name, _)| format!("key.{name}"))
+            .chain(
+                model
+                    .secret
+                    .iter()
+                    .flatten()
+                    .map(|(name, _)| format!("secret.{name}")),
+            );
+        let mut findings = Vec::new();
+        for id in ids {
+            let (stores, data) = protects(model, &id);
+            if stores.is_empty() && data.is_empty() {
+                continue;
+            }
+            let holders = graph.reverse_neighbors(&id, Relation::Holds);
+            let mut found = None;
+            for holder in holders.iter().filter(|h| h.kind() == "store") {
+                if stores.contains(holder.as_str()) {
+                    found = Some((
+                        Severity::Error,
+                        format!("`{id}` is held in `{holder}`, which it protects"),
+                        vec![holder.to_string()],
+                    ));
+                    break;
+                }
+                let alongside = graph
+                    .neighbors(holder, Relation::StoresData)
+                    .into_iter()
+                    .find(|d| data.contains(d.as_str()));
+                if let Some(d) = alongside {
+                    found = Some((
+                        Severity::Error,
+                        format!("`{id}` is held in `{holder}` alongside `{d}`, which it protects"),
+                        vec![holder.to_string(), d.to_string()],
+                    ));
+                    break;
+                }
+            }
+            if found.is_none() {
+                let hosts: IndexSet<&str> = holders
+                    .iter()
+                    .flat_map(|h| hosts_of(model, h.as_str()))
+                    .collect();
+                found = stores.iter().find_map(|store| {
+                    let host = hosts_of(model, store)
+                        .into_iter()
+                        .find(|h| hosts.contains(h))?;
+                    Some((
+                        Severity::Warning,
+                        format!("`{id}` is held on `{host}` with `{store}`, which it protects"),
+                        vec![host.to_string(), store.to_string()],
+                    ))
+                });
+                // or a store on the same host holds data it protects
+                found = found.or_else(|| {
+                    hosts.iter().find_map(|host| {
+                        model.host_stores(host).into_iter().find_map(|store| {
+                            let d = graph
+                                .neighbors(&store, Relation::StoresData)
+                                .into_iter()
+                                .find(|d| data.contains(d.as_str()))?;
+                            Some((
+                                Severity::Warning,
+                                format!(
+                                    "`{id}` is held on `{host}` with `{store}` alongside `{d}`, which it protects"
+                                ),
+                                vec![host.to_string(), store.to_string(), d.to_string()],
+                            ))
+                        })
+                    })
+                });
+            }
+            let Some((severity, message, with)) = found else {
+                continue;
+            };
+            let Ok(entity) = id.parse::<EntityRef>() else {
+                continue;
+            };
+            let mut entities = vec![entity];
+            entities.extend(with.into_iter().filter_map(|e| e.parse().ok()));
+            findings.push(Finding {
+                rule: self.name().to_string(),
+                severity,
+                message,
+                entities,
+                location: model.sources.locate(&id),
+            });
+        }
+        findings
+    }
+}
+
+// the stores a key or secret protects, and the data in them or carried
+// over the channels it protects
+fn protects<'a>(model: &'a Model, id: &str) -> (IndexSet<&'a str>, IndexSet<&'a str>) {
+    let graph = model.graph();
+    let mut stores = IndexSet::new();
+    let mut data = IndexSet::new();
+    let schemes = graph
+        .reverse_neighbors(id, Relation::Uses)
+        .into_iter()
+        .filter(|s| matches!(s.kind(), "authentication" | "encryption"));
+    for scheme in schemes {
+        for user in graph.reverse_neighbors(scheme, Relation::Uses) {
+            match user.kind() {
+                "store" => {
+                    stores.insert(user.as_str());
+                    let held = graph.neighbors(user, Relation::StoresData);
+                    data.extend(held.into_iter().map(EntityRef::as_str));
+                }
+                "channel" => {
+                    let flows = graph
+                        .reverse_neighbors(user, Relation::Uses)
+                        .into_iter()
+                        .filter(|f| f.kind() == "flow");
+                    for flow in flows {
+                        let carried = graph.neighbors(flow, Relation::CarriesData);
+                        data.extend(carried.into_iter().map(EntityRef::as_str));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    (stores, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluate_rules;
+
+    #[test]
+    fn keys() {
+        let model: Model = toml::from_str(
+            r#"
+[server.db]
+stores = ["store.db", "store.keys"]
+[server.hsm]
+[server.app]
+stores = ["store.config"]
+
+[data.records]
+[data.sessions]
+[store.db]
+data = ["data.records"]
+encryption = "encryption.disk"
+[store.keys]
+[store.config]
+data = ["data.sessions"]
+
+[encryption.disk]
+keys = ["key.disk", "key.backup"]
+[encryption.tls]
+keys = ["key.tls", "key.hsm"]
+[authentication.token]
+secrets = ["secret.token"]
+
+[channel.https]
+encryption = "encryption.tls"
+authentication = "authentication.token"
+[flow.login]
+channel = "channel.https"
+data = ["data.sessions"]
+
+[key.disk]
+store = "store.db"
+[key.backup]
+store = "store.keys"
+[key.tls]
+store = "store.config"
+[key.hsm]
+server = "server.hsm"
+[secret.token]
+server = "server.app"
+rotation_days = 90
+rotated = "2024-01-01"
+"#,
+        )
+        .unwrap();
+        let findings: Vec<(Severity, String)> = evaluate_rules(&model, &[Box::new(KeyWithData)])
+            .into_iter()
+            .map(|f| (f.severity, f.message))
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    Severity::Error,
+                    "`key.disk` is held in `store.db`, which it protects".to_string()
+                ),
+                (
+                    Severity::Warning,
+                    "`key.backup` is held on `server.db` with `store.db`, which it protects"
+                        .to_string()
+                ),
+                (
+                    Severity::Error,
+                    "`key.tls` is held in `store.config` alongside `data.sessions`, which it protects"
+                        .to_string()
+                ),
+                (
+                    Severity::Warning,
+                    "`secret.token` is held on `server.app` with `store.config` alongside `data.sessions`, which it protects"
+                        .to_string()
+                ),
+            ],
+            findings
+        );
+        let token = &model.secret_by_id("secret.token").unwrap().custody;
+        assert_eq!(token.rotation_due(), "2024-03-31".parse().ok());
+        assert!(token.rotation_overdue_on("2024-04-01".parse().unwrap()));
+        assert!(!token.rotation_overdue_on("2024-03-31".parse().unwrap()));
+    }
+}
@@ -1,29 +1,84 @@
 use serde::{Deserialize, Serialize};
 //use std::collections::HashMap;
+use chrono::NaiveDate;
 use indexmap::IndexMap;
 use std::fmt;
-use std::fs;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::OnceLock;
 use thiserror::Error;
 
+mod access;
+mod backup;
+mod boundary;
+mod classification;
+mod date;
+mod firewall;
+mod graph;
+mod ipv6;
+mod keys;
+mod lineage;
+mod load;
+mod policy;
+mod reach;
+mod reference;
+mod register;
+mod rules;
+mod schemes;
+mod scoring;
+mod source;
+mod surface;
+mod validate;
+pub use access::{
+    access_coverage, scheme_users, untrusted_networks, AccessCoverage, AccessGap,
+    UncontrolledAccess,
+};
+pub use backup::{BackupOnSameServer, UnverifiedOffsiteBackup};
+pub use boundary::{boundaries_of, boundary_crossings, BoundaryCrossing};
+pub use firewall::{DetachedFlowController, FlowNotAllowed, UnusedFlowControl};
+pub use graph::{Edge, ModelGraph, Relation};
+pub use ipnet::{IpNet, Ipv4Net, Ipv6Net};
+pub use ipv6::{Ipv6UncontrolledChannel, Ipv6WithoutIngressControls, Ipv6WithoutRanges, IPV6};
+pub use keys::KeyWithData;
+pub use lineage::{lineage, Lineage};
+pub use load::load;
+pub use policy::{load_policy, Condition, Outcome, PolicyRule, Related, Step};
+pub use reach::{reach, reachability, Hop, Reachability, Route};
+pub use reference::{AnyEntity, EntityKind, EntityRef, Lookup};
+pub use register::{entity_risks, risk_register, RegisterEntry, Treatment};
+pub use rules::{builtin_rules, evaluate, evaluate_rules, Finding, Rule, UnencryptedSensitiveFlow};
+pub use schemes::{SingleFactorAuthentication, WeakEncryption};
+pub use scoring::{score_risk, score_risks, RiskScore, MITIGATED, REMEDIATED};
+pub use source::{Location, SourceMap};
+pub use surface::{attack_surface, attack_surfaces, AttackSurface};
+pub use validate::{validate, Diagnostic, Severity};
+
 #[allow(dead_code)]
 pub enum EntityID {
+    Acceptance(String),
     Actor(String),
     Agent(String),
     Application(String),
     Authentication(String),
     Authorization(String),
+    Backup(String),
+    Boundary(String),
     Channel(String),
     Classification(String),
+    Comment(String),
     Control(String),
     Data(String),
+    Deployment(String),
     Encryption(String),
     Endpoint(String),
     Flow(String),
+    Key(String),
     Network(String),
     Process(String),
     Protocol(String),
     Regulation(String),
     Risk(String),
+    Secret(String),
     Server(String),
     Store(String),
     Threat(String),
@@ -33,28 +88,47 @@ pub enum EntityID {
 pub enum EntityError {
     #[error("Unrecognized entity type: {0}")]
     UnrecognizedEntityType(String),
+    #[error("Malformed entity ID `{0}`, expected `<type>.<name>`")]
+    MalformedID(String),
+    #[error("Entity `{id}` has the wrong type, expected `{expected}`")]
+    UnexpectedEntityType { id: String, expected: &'static str },
+}
+
+#[derive(Error, Debug)]
+pub enum LookupError {
+    #[error("Unknown entity type in `{0}`")]
+    UnknownEntityType(String),
+    #[error("No entity `{0}` in the model")]
+    NotFound(String),
 }
 
 impl fmt::Display for EntityID {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            EntityID::Acceptance(id) => write!(f, "acceptance.{id}"),
             EntityID::Actor(id) => write!(f, "actor.{id}"),
             EntityID::Agent(id) => write!(f, "agent.{id}"),
             EntityID::Application(id) => write!(f, "application.{id}"),
             EntityID::Authentication(id) => write!(f, "authentication.{id}"),
             EntityID::Authorization(id) => write!(f, "authorization.{id}"),
+            EntityID::Backup(id) => write!(f, "backup.{id}"),
+            EntityID::Boundary(id) => write!(f, "boundary.{id}"),
             EntityID::Channel(id) => write!(f, "channel.{id}"),
             EntityID::Classification(id) => write!(f, "classification.{id}"),
+            EntityID::Comment(id) => write!(f, "comment.{id}"),
             EntityID::Control(id) => write!(f, "control.{id}"),
             EntityID::Data(id) => write!(f, "data.{id}"),
+            EntityID::Deployment(id) => write!(f, "deployment.{id}"),
             EntityID::Encryption(id) => write!(f, "encryption.{id}"),
             EntityID::Endpoint(id) => write!(f, "endpoint.{id}"),
             EntityID::Flow(id) => write!(f, "flow.{id}"),
+            EntityID::Key(id) => write!(f, "key.{id}"),
             EntityID::Network(id) => write!(f, "network.{id}"),
             EntityID::Process(id) => write!(f, "process.{id}"),
             EntityID::Protocol(id) => write!(f, "protocol.{id}"),
             EntityID::Regulation(id) => write!(f, "regulation.{id}"),
             EntityID::Risk(id) => write!(f, "risk.{id}"),
+            EntityID::Secret(id) => write!(f, "secret.{id}"),
             EntityID::Server(id) => write!(f, "server.{id}"),
             EntityID::Store(id) => write!(f, "store.{id}"),
             EntityID::Threat(id) => write!(f, "threat.{id}"),
@@ -62,29 +136,38 @@ impl fmt::Display for EntityID {
     }
 }
 
-impl EntityID {
-    pub fn from_str(id: &str) -> Result<EntityID, EntityError> {
-        let mut parts = id.split(".");
+impl FromStr for EntityID {
+    type Err = EntityError;
+
+    fn from_str(id: &str) -> Result<EntityID, EntityError> {
+        let mut parts = id.split('.');
         let etype = parts.next().unwrap();
         let name = parts.collect::<Vec<&str>>().join(".");
         match etype {
+            "acceptance" => Ok(EntityID::Acceptance(name)),
             "actor" => Ok(EntityID::Actor(name)),
             "agent" => Ok(EntityID::Agent(name)),
             "application" => Ok(EntityID::Application(name)),
             "authentication" => Ok(EntityID::Authentication(name)),
             "authorization" => Ok(EntityID::Authorization(name)),
+            "backup" => Ok(EntityID::Backup(name)),
+            "boundary" => Ok(EntityID::Boundary(name)),
             "channel" => Ok(EntityID::Channel(name)),
             "classification" => Ok(EntityID::Classification(name)),
+            "comment" => Ok(EntityID::Comment(name)),
             "control" => Ok(EntityID::Control(name)),
             "data" => Ok(EntityID::Data(name)),
+            "deployment" => Ok(EntityID::Deployment(name)),
             "encryption" => Ok(EntityID::Encryption(name)),
             "endpoint" => Ok(EntityID::Endpoint(name)),
             "flow" => Ok(EntityID::Flow(name)),
+            "key" => Ok(EntityID::Key(name)),
             "network" => Ok(EntityID::Network(name)),
             "process" => Ok(EntityID::Process(name)),
             "protocol" => Ok(EntityID::Protocol(name)),
             "regulation" => Ok(EntityID::Regulation(name)),
             "risk" => Ok(EntityID::Risk(name)),
+            "secret" => Ok(EntityID::Secret(name)),
             "server" => Ok(EntityID::Server(name)),
             "store" => Ok(EntityID::Store(name)),
             "threat" => Ok(EntityID::Threat(name)),
@@ -103,39 +186,52 @@ pub trait Entity {
 }
 
 pub type Markdown = String;
+// A rule letting traffic in to, or out of, a network, or stopping it.  A
+// rule without `flows`, `sources` or `destinations` applies to all of them.
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
-struct FlowControl {
-    description: Option<Markdown>,
-    mitigates: Option<Vec<RiskID>>,
-    remediates: Option<Vec<RiskID>>,
-    flows: Option<Vec<FlowID>>,
-    controller: Option<String>,
-    sources: Option<Vec<String>>,
-    destinations: Option<Vec<String>>,
-    action: Option<FlowControlAction>,
+pub struct FlowControl {
+    pub description: Option<Markdown>,
+    pub mitigates: Option<Vec<RiskID>>,
+    pub remediates: Option<Vec<RiskID>>,
+    pub flows: Option<Vec<FlowID>>,
+    // the server enforcing the rule, such as a firewall
+    pub controller: Option<ServerID>,
+    pub sources: Option<Vec<EntityRef>>,
+    pub destinations: Option<Vec<EntityRef>>,
+    // allow when not given
+    pub action: Option<FlowControlAction>,
 }
 
-#[derive(Serialize, Deserialize)]
-enum FlowControlAction {
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowControlAction {
     #[serde(alias = "allow", alias = "ALLOW")]
     Allow,
     #[serde(alias = "deny", alias = "DENY")]
     Deny,
 }
 
-pub type DataID = String;
+impl fmt::Display for FlowControlAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlowControlAction::Allow => write!(f, "Allow"),
+            FlowControlAction::Deny => write!(f, "Deny"),
+        }
+    }
+}
+
+pub type DataID = EntityRef<Data>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Data {
     pub title: Option<String>,
     pub description: Option<Markdown>,
     pub contains: Option<Vec<DataID>>,
-    pub classification: Option<String>,
+    pub classification: Option<ClassificationID>,
     pub regulations: Option<Vec<RegulationID>>,
-    pub subjects: Option<Vec<String>>,
+    pub subjects: Option<Vec<EntityRef>>,
     pub owner: Option<ActorID>,
-    pub steward: Option<String>,
+    pub steward: Option<EntityRef>,
     pub format: Option<String>,
     pub risks: Option<Vec<RiskID>>,
     pub controls: Option<Vec<ControlID>>,
@@ -146,12 +242,12 @@ impl Entity for Data {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
 #[allow(dead_code)]
-pub type StoreID = String;
+pub type StoreID = EntityRef<Store>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Store {
@@ -164,7 +260,7 @@ pub struct Store {
     pub authentication: Option<AuthenticationID>,
     pub authorization: Option<AuthorizationID>,
     pub controls: Option<Vec<ControlID>>,
-    pub backup: Option<String>,
+    pub backup: Option<BackupID>,
     pub risks: Option<Vec<RiskID>>,
 }
 
@@ -173,19 +269,80 @@ impl Entity for Store {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
-pub type FlowID = String;
+pub type BackupID = EntityRef<Backup>;
+// How a store is backed up: where the copies go, how often, for how long,
+// and when a restore from them was last verified.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Backup {
+    pub title: Option<String>,
+    pub description: Option<Markdown>,
+    // the store the copies are written to
+    pub target: Option<StoreID>,
+    pub frequency: Option<BackupFrequency>,
+    pub retention_days: Option<u32>,
+    // whether the copies are kept away from the site of the store
+    pub offsite: Option<bool>,
+    pub encryption: Option<EncryptionID>,
+    // when a restore from the backup was last verified
+    #[serde(default, deserialize_with = "date::optional")]
+    pub verified: Option<NaiveDate>,
+    pub risks: Option<Vec<RiskID>>,
+}
+
+impl Backup {
+    // an offsite backup which has been restored from
+    #[must_use]
+    pub fn verified_offsite(&self) -> bool {
+        self.offsite == Some(true) && self.verified.is_some()
+    }
+}
+
+impl Entity for Backup {
+    fn get_title(&self, id: &str) -> String {
+        self.title.clone().unwrap_or(id.to_string())
+    }
+    fn get_description(&self) -> String {
+        self.description.clone().unwrap_or_default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackupFrequency {
+    Continuous,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl fmt::Display for BackupFrequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BackupFrequency::Continuous => "Continuous",
+            BackupFrequency::Hourly => "Hourly",
+            BackupFrequency::Daily => "Daily",
+            BackupFrequency::Weekly => "Weekly",
+            BackupFrequency::Monthly => "Monthly",
+        };
+        write!(f, "{name}")
+    }
+}
+
+pub type FlowID = EntityRef<Flow>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Flow {
     pub title: Option<String>,
     pub description: Option<Markdown>,
     pub channel: Option<ChannelID>,
-    pub sources: Option<Vec<String>>,
-    pub destinations: Option<Vec<String>>,
+    pub sources: Option<Vec<EntityRef>>,
+    pub destinations: Option<Vec<EntityRef>>,
     pub data: Option<Vec<DataID>>,
     pub risks: Option<Vec<RiskID>>,
 }
@@ -195,16 +352,18 @@ impl Entity for Flow {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
-pub type ClassificationID = String;
+pub type ClassificationID = EntityRef<Classification>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Classification {
     pub title: Option<String>,
     pub description: Option<Markdown>,
+    // higher levels are more restrictive
+    pub level: Option<u32>,
 }
 
 impl Entity for Classification {
@@ -212,11 +371,11 @@ impl Entity for Classification {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
-pub type ControlID = String;
+pub type ControlID = EntityRef<Control>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Control {
@@ -232,30 +391,47 @@ impl Entity for Control {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct NetworkControls {
-    description: Option<Markdown>,
-    ingress_default: Option<FlowControlAction>,
-    egress_default: Option<FlowControlAction>,
-    ingress: Option<Vec<FlowControl>>,
-    egress: Option<Vec<FlowControl>>,
+    pub description: Option<Markdown>,
+    // what happens to traffic no rule matches, allow when not given
+    pub ingress_default: Option<FlowControlAction>,
+    pub egress_default: Option<FlowControlAction>,
+    pub ingress: Option<Vec<FlowControl>>,
+    pub egress: Option<Vec<FlowControl>>,
+}
+
+impl NetworkControls {
+    // the servers enforcing any of the rules, in the order first named
+    #[must_use]
+    pub fn controllers(&self) -> Vec<&ServerID> {
+        let mut controllers: Vec<&ServerID> = Vec::new();
+        for rule in self.ingress.iter().chain(self.egress.iter()).flatten() {
+            if let Some(controller) = &rule.controller {
+                if !controllers.contains(&controller) {
+                    controllers.push(controller);
+                }
+            }
+        }
+        controllers
+    }
 }
 
-pub type NetworkID = String;
+pub type NetworkID = EntityRef<Network>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Network {
     pub title: Option<String>,
     pub description: Option<Markdown>,
     pub interfaces: Option<Vec<NetworkInterface>>,
-    pub protocols: Option<Vec<String>>,
-    pub ipv4_ranges: Option<Vec<String>>,
-    pub ipv6_ranges: Option<Vec<String>>,
+    pub protocols: Option<Vec<ProtocolID>>,
+    pub ipv4_ranges: Option<Vec<Ipv4Net>>,
+    pub ipv6_ranges: Option<Vec<Ipv6Net>>,
     pub controls: Option<NetworkControls>,
     pub host_networks: Option<Vec<NetworkID>>,
     pub vpn: Option<bool>,
@@ -263,38 +439,52 @@ pub struct Network {
 }
 
 impl Network {
-    pub fn peers_upstream(&self) -> Vec<String> {
+    #[must_use]
+    pub fn peers_upstream(&self) -> Vec<NetworkID> {
         let mut peers = Vec::new();
         if let Some(interfaces) = &self.interfaces {
-            for i in interfaces.iter() {
+            for i in interfaces {
                 if let Some(network) = &i.network {
                     if !i.downstream.unwrap_or(false) {
-                        peers.push(network.clone())
+                        peers.push(network.clone());
                     }
                 }
             }
         }
         peers
     }
-    pub fn peers_downstream(&self) -> Vec<String> {
+    #[must_use]
+    pub fn peers_downstream(&self) -> Vec<NetworkID> {
         let mut peers = Vec::new();
         if let Some(interfaces) = &self.interfaces {
-            for i in interfaces.iter() {
+            for i in interfaces {
                 if let Some(network) = &i.network {
                     if i.downstream.unwrap_or(false) {
-                        peers.push(network.clone())
+                        peers.push(network.clone());
                     }
                 }
             }
         }
         peers
     }
-    pub fn peers(&self) -> Vec<String> {
+    // the IPv4 ranges, then the IPv6 ranges
+    #[must_use]
+    pub fn ranges(&self) -> Vec<IpNet> {
+        let v4 = self.ipv4_ranges.iter().flatten().map(|r| IpNet::V4(*r));
+        let v6 = self.ipv6_ranges.iter().flatten().map(|r| IpNet::V6(*r));
+        v4.chain(v6).collect()
+    }
+    #[must_use]
+    pub fn contains(&self, address: &IpAddr) -> bool {
+        self.ranges().iter().any(|r| r.contains(address))
+    }
+    #[must_use]
+    pub fn peers(&self) -> Vec<NetworkID> {
         let mut peers = Vec::new();
         if let Some(interfaces) = &self.interfaces {
-            for i in interfaces.iter() {
+            for i in interfaces {
                 if let Some(network) = &i.network {
-                    peers.push(network.clone())
+                    peers.push(network.clone());
                 }
             }
         }
@@ -307,7 +497,7 @@ impl Entity for Network {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
@@ -317,12 +507,12 @@ pub struct NetworkInterface {
     pub description: Option<Markdown>,
     pub downstream: Option<bool>,
     pub network: Option<NetworkID>,
-    pub address: Option<String>,
+    pub address: Option<IpAddr>,
     pub hostnames: Option<Vec<String>>,
 }
 
 #[allow(dead_code)]
-pub type ServerID = String;
+pub type ServerID = EntityRef<Server>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Server {
@@ -341,36 +531,115 @@ impl Entity for Server {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
-#[allow(dead_code)]
-pub type DeploymentID = String;
+pub type DeploymentID = EntityRef<Deployment>;
+// Applications and stores put on to each of a set of servers, as an
+// alternative to the servers listing them.
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Deployment {
+    pub title: Option<String>,
     pub description: Option<Markdown>,
     pub targets: Option<Vec<ServerID>>,
     pub applications: Option<Vec<ApplicationID>>,
     pub stores: Option<Vec<StoreID>>,
+    pub risks: Option<Vec<RiskID>>,
+}
+
+impl Entity for Deployment {
+    fn get_title(&self, id: &str) -> String {
+        self.title.clone().unwrap_or(id.to_string())
+    }
+    fn get_description(&self) -> String {
+        self.description.clone().unwrap_or_default()
+    }
+}
+
+pub type BoundaryID = EntityRef<Boundary>;
+// A trust boundary around networks, hosts or applications, within which
+// everything is trusted alike.  A server, endpoint or application not
+// listed is within the boundaries of what it runs on.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Boundary {
+    pub title: Option<String>,
+    pub description: Option<Markdown>,
+    // higher levels are more trusted
+    pub trust: Option<u32>,
+    pub networks: Option<Vec<NetworkID>>,
+    pub servers: Option<Vec<ServerID>>,
+    pub endpoints: Option<Vec<EndpointID>>,
+    pub applications: Option<Vec<ApplicationID>>,
+    pub risks: Option<Vec<RiskID>>,
+}
+
+impl Entity for Boundary {
+    fn get_title(&self, id: &str) -> String {
+        self.title.clone().unwrap_or(id.to_string())
+    }
+    fn get_description(&self) -> String {
+        self.description.clone().unwrap_or_default()
+    }
+}
+
+pub type AcceptanceID = EntityRef<Acceptance>;
+// A decision to accept a risk on some entities, rather than control it,
+// which auditors can trace to who approved it and for how long.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Acceptance {
+    pub title: Option<String>,
+    pub description: Option<Markdown>,
+    pub risk: Option<RiskID>,
+    pub entities: Option<Vec<EntityRef>>,
+    pub approver: Option<ActorID>,
+    pub rationale: Option<Markdown>,
+    #[serde(default, deserialize_with = "date::optional")]
+    pub granted: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "date::optional")]
+    pub expires: Option<NaiveDate>,
+}
+
+impl Acceptance {
+    // an acceptance without an expiry date never expires
+    #[must_use]
+    pub fn expired_on(&self, date: NaiveDate) -> bool {
+        self.expires.is_some_and(|expires| expires < date)
+    }
+
+    #[must_use]
+    pub fn expired(&self) -> bool {
+        self.expired_on(chrono::Local::now().date_naive())
+    }
+}
+
+impl Entity for Acceptance {
+    fn get_title(&self, id: &str) -> String {
+        self.title.clone().unwrap_or(id.to_string())
+    }
+    fn get_description(&self) -> String {
+        self.description.clone().unwrap_or_default()
+    }
 }
 
 pub type Port = usize;
 
-pub type ChannelID = String;
+pub type ChannelID = EntityRef<Channel>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Channel {
     pub title: Option<String>,
     pub description: Option<Markdown>,
-    pub over: Option<Vec<NetworkID>>,
-    pub protocols: Option<Vec<String>>,
+    pub over: Option<Vec<ChannelID>>,
+    pub protocols: Option<Vec<ProtocolID>>,
     pub ports: Option<Vec<Port>>,
-    pub encryption: Option<String>,
-    pub authentication: Option<String>,
-    pub authorization: Option<String>,
-    pub controls: Option<Vec<String>>,
+    pub encryption: Option<EncryptionID>,
+    pub authentication: Option<AuthenticationID>,
+    pub authorization: Option<AuthorizationID>,
+    pub controls: Option<Vec<ControlID>>,
     pub risks: Option<Vec<RiskID>>,
 }
 
@@ -379,11 +648,11 @@ impl Entity for Channel {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
-pub type ApplicationID = String;
+pub type ApplicationID = EntityRef<Application>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Application {
@@ -398,12 +667,12 @@ impl Entity for Application {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
 #[allow(dead_code)]
-pub type ProcessID = String;
+pub type ProcessID = EntityRef<Process>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Process {
@@ -417,12 +686,12 @@ impl Entity for Process {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
 #[allow(dead_code)]
-pub type ProtocolID = String;
+pub type ProtocolID = EntityRef<Protocol>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Protocol {
@@ -436,12 +705,12 @@ impl Entity for Protocol {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
 #[allow(dead_code)]
-pub type EndpointID = String;
+pub type EndpointID = EntityRef<Endpoint>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Endpoint {
@@ -449,7 +718,7 @@ pub struct Endpoint {
     pub description: Option<Markdown>,
     pub interfaces: Option<Vec<NetworkInterface>>,
     pub applications: Option<Vec<ApplicationID>>,
-    pub agents: Option<Vec<ApplicationID>>,
+    pub agents: Option<Vec<AgentID>>,
     pub stores: Option<Vec<StoreID>>,
     pub owner: Option<ActorID>,
     pub os: Option<String>,
@@ -462,16 +731,70 @@ impl Entity for Endpoint {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
-pub type RiskID = String;
+pub type RiskID = EntityRef<Risk>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Risk {
     pub title: Option<String>,
     pub description: Option<Markdown>,
+    pub likelihood: Option<Rating>,
+    pub impact: Option<Rating>,
+    pub scoring: Option<RiskScoring>,
+}
+
+// A five point scale, used for the likelihood and impact of risks.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rating {
+    VeryLow = 1,
+    Low = 2,
+    Medium = 3,
+    High = 4,
+    VeryHigh = 5,
+}
+
+impl Rating {
+    pub const ALL: [Rating; 5] = [
+        Rating::VeryLow,
+        Rating::Low,
+        Rating::Medium,
+        Rating::High,
+        Rating::VeryHigh,
+    ];
+
+    #[must_use]
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rating::VeryLow => write!(f, "Very Low"),
+            Rating::Low => write!(f, "Low"),
+            Rating::Medium => write!(f, "Medium"),
+            Rating::High => write!(f, "High"),
+            Rating::VeryHigh => write!(f, "Very High"),
+        }
+    }
+}
+
+// A quantitative basis for a risk's rating, e.g.
+// `scoring = { model = "cvss", score = 7.5 }`, or
+// `scoring = { model = "fair", frequency = 0.5, magnitude = 250000 }`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(tag = "model", rename_all = "lowercase", deny_unknown_fields)]
+pub enum RiskScoring {
+    // a CVSS base score, from 0 to 10, which rates the impact
+    Cvss { score: f64 },
+    // the FAIR loss event frequency per year, which rates the likelihood,
+    // and the loss magnitude of each event
+    Fair { frequency: f64, magnitude: f64 },
 }
 
 impl Entity for Risk {
@@ -479,21 +802,79 @@ impl Entity for Risk {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
 #[allow(dead_code)]
-pub type ThreatID = String;
+pub type ThreatID = EntityRef<Threat>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Threat {
     pub title: Option<String>,
     pub description: Option<Markdown>,
-    pub sophistication: Option<String>,
+    pub sophistication: Option<Sophistication>,
+    pub capability: Option<Capability>,
     pub motivation: Option<Markdown>,
     pub risk: Option<Vec<RiskID>>,
     pub actor: Option<Vec<ActorID>>,
+    // the networks the threat acts from, besides those of its actors
+    pub networks: Option<Vec<NetworkID>>,
+}
+
+// How skilled a threat actor is, following the STIX vocabulary.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum Sophistication {
+    None,
+    Minimal,
+    Intermediate,
+    Advanced,
+    Expert,
+    Innovator,
+    Strategic,
+}
+
+// The resources a threat actor can bring to bear, following the STIX
+// resource levels.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    Individual,
+    Club,
+    Contest,
+    Team,
+    Organization,
+    Government,
+}
+
+impl fmt::Display for Sophistication {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Sophistication::None => "None",
+            Sophistication::Minimal => "Minimal",
+            Sophistication::Intermediate => "Intermediate",
+            Sophistication::Advanced => "Advanced",
+            Sophistication::Expert => "Expert",
+            Sophistication::Innovator => "Innovator",
+            Sophistication::Strategic => "Strategic",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Capability::Individual => "Individual",
+            Capability::Club => "Club",
+            Capability::Contest => "Contest",
+            Capability::Team => "Team",
+            Capability::Organization => "Organization",
+            Capability::Government => "Government",
+        };
+        write!(f, "{name}")
+    }
 }
 
 impl Entity for Threat {
@@ -501,11 +882,11 @@ impl Entity for Threat {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
-pub type ActorID = String;
+pub type ActorID = EntityRef<Actor>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Actor {
@@ -519,11 +900,11 @@ impl Entity for Actor {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
-pub type AgentID = String;
+pub type AgentID = EntityRef<Agent>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Agent {
@@ -541,11 +922,11 @@ impl Entity for Agent {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
-pub type RegulationID = String;
+pub type RegulationID = EntityRef<Regulation>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Regulation {
@@ -558,16 +939,96 @@ impl Entity for Regulation {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
-pub type AuthenticationID = String;
+pub type AuthenticationID = EntityRef<Authentication>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Authentication {
     pub title: Option<String>,
     pub description: Option<Markdown>,
+    pub mfa: Option<bool>,
+    pub factors: Option<Vec<AuthenticationFactor>>,
+    // resists credentials being relayed to an attacker, e.g. FIDO2
+    pub phishing_resistant: Option<bool>,
+    pub keys: Option<Vec<KeyID>>,
+    pub secrets: Option<Vec<SecretID>>,
+}
+
+impl Authentication {
+    // whether more than one factor is required, as given by `mfa`, or
+    // failing that by whether more than one kind of factor is listed
+    #[must_use]
+    pub fn multi_factor(&self) -> Option<bool> {
+        self.mfa.or_else(|| {
+            let factors = self.factors.as_ref()?;
+            let mut kinds: Vec<FactorKind> = factors.iter().map(|f| f.kind()).collect();
+            kinds.sort();
+            kinds.dedup();
+            Some(kinds.len() > 1)
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuthenticationFactor {
+    Password,
+    Pin,
+    SharedSecret,
+    Otp,
+    Sms,
+    Push,
+    Certificate,
+    PublicKey,
+    HardwareKey,
+    Biometric,
+}
+
+// Something the user knows, has or is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FactorKind {
+    Knowledge,
+    Possession,
+    Inherence,
+}
+
+impl AuthenticationFactor {
+    #[must_use]
+    pub fn kind(self) -> FactorKind {
+        match self {
+            AuthenticationFactor::Password
+            | AuthenticationFactor::Pin
+            | AuthenticationFactor::SharedSecret => FactorKind::Knowledge,
+            AuthenticationFactor::Otp
+            | AuthenticationFactor::Sms
+            | AuthenticationFactor::Push
+            | AuthenticationFactor::Certificate
+            | AuthenticationFactor::PublicKey
+            | AuthenticationFactor::HardwareKey => FactorKind::Possession,
+            AuthenticationFactor::Biometric => FactorKind::Inherence,
+        }
+    }
+}
+
+impl fmt::Display for AuthenticationFactor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AuthenticationFactor::Password => "Password",
+            AuthenticationFactor::Pin => "PIN",
+            AuthenticationFactor::SharedSecret => "Shared Secret",
+            AuthenticationFactor::Otp => "One Time Password",
+            AuthenticationFactor::Sms => "SMS",
+            AuthenticationFactor::Push => "Push Notification",
+            AuthenticationFactor::Certificate => "Certificate",
+            AuthenticationFactor::PublicKey => "Public Key",
+            AuthenticationFactor::HardwareKey => "Hardware Key",
+            AuthenticationFactor::Biometric => "Biometric",
+        };
+        write!(f, "{name}")
+    }
 }
 
 impl Entity for Authentication {
@@ -575,16 +1036,42 @@ impl Entity for Authentication {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
-pub type AuthorizationID = String;
+pub type AuthorizationID = EntityRef<Authorization>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Authorization {
     pub title: Option<String>,
     pub description: Option<Markdown>,
+    pub model: Option<AuthorizationModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuthorizationModel {
+    // role based access control
+    Rbac,
+    // attribute based access control
+    Abac,
+    // access control lists
+    Acl,
+    // anyone authenticated may do anything
+    None,
+}
+
+impl fmt::Display for AuthorizationModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AuthorizationModel::Rbac => "RBAC",
+            AuthorizationModel::Abac => "ABAC",
+            AuthorizationModel::Acl => "ACL",
+            AuthorizationModel::None => "None",
+        };
+        write!(f, "{name}")
+    }
 }
 
 impl Entity for Authorization {
@@ -592,16 +1079,25 @@ impl Entity for Authorization {
         self.title.clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
     }
 }
 
-pub type EncryptionID = String;
+pub type EncryptionID = EntityRef<Encryption>;
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Encryption {
     pub title: Option<String>,
     pub description: Option<Markdown>,
+    // e.g. "AES-256-GCM", "ChaCha20-Poly1305"
+    pub algorithms: Option<Vec<String>>,
+    // in bits
+    pub key_length: Option<u32>,
+    // e.g. "TLS 1.2", "TLS 1.3"
+    pub protocol_versions: Option<Vec<String>>,
+    // how keys are generated, stored and rotated
+    pub key_management: Option<Markdown>,
+    pub keys: Option<Vec<KeyID>>,
 }
 
 impl Entity for Encryption {
@@ -609,7 +1105,83 @@ impl Entity for Encryption {
         self.title.clone().clone().unwrap_or(id.to_string())
     }
     fn get_description(&self) -> String {
-        self.description.clone().unwrap_or("".to_string())
+        self.description.clone().unwrap_or_default()
+    }
+}
+
+// Where a key or secret is held, who owns it, and how often it is rotated.
+// It is held in a store, or by a server such as an HSM.
+#[derive(Serialize, Deserialize)]
+pub struct Custody {
+    pub store: Option<StoreID>,
+    pub server: Option<ServerID>,
+    pub owner: Option<ActorID>,
+    // how often it should be rotated
+    pub rotation_days: Option<u32>,
+    // when it was last rotated
+    #[serde(default, deserialize_with = "date::optional")]
+    pub rotated: Option<NaiveDate>,
+}
+
+impl Custody {
+    // the date it is next to be rotated, when both its rotation period and
+    // last rotation are known
+    #[must_use]
+    pub fn rotation_due(&self) -> Option<NaiveDate> {
+        let days = chrono::Days::new(u64::from(self.rotation_days?));
+        self.rotated?.checked_add_days(days)
+    }
+
+    #[must_use]
+    pub fn rotation_overdue_on(&self, date: NaiveDate) -> bool {
+        self.rotation_due().is_some_and(|due| due < date)
+    }
+
+    #[must_use]
+    pub fn rotation_overdue(&self) -> bool {
+        self.rotation_overdue_on(chrono::Local::now().date_naive())
+    }
+}
+
+pub type KeyID = EntityRef<Key>;
+// A cryptographic key.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Key {
+    pub title: Option<String>,
+    pub description: Option<Markdown>,
+    #[serde(flatten)]
+    pub custody: Custody,
+    pub risks: Option<Vec<RiskID>>,
+}
+
+impl Entity for Key {
+    fn get_title(&self, id: &str) -> String {
+        self.title.clone().unwrap_or(id.to_string())
+    }
+    fn get_description(&self) -> String {
+        self.description.clone().unwrap_or_default()
+    }
+}
+
+pub type SecretID = EntityRef<Secret>;
+// A password, token or other credential, held and rotated as keys are.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Secret {
+    pub title: Option<String>,
+    pub description: Option<Markdown>,
+    #[serde(flatten)]
+    pub custody: Custody,
+    pub risks: Option<Vec<RiskID>>,
+}
+
+impl Entity for Secret {
+    fn get_title(&self, id: &str) -> String {
+        self.title.clone().unwrap_or(id.to_string())
+    }
+    fn get_description(&self) -> String {
+        self.description.clone().unwrap_or_default()
     }
 }
 
@@ -617,14 +1189,24 @@ impl Entity for Encryption {
 #[serde(deny_unknown_fields)]
 pub struct Comment {
     pub title: Option<String>,
-    pub entity: String,
+    pub entity: EntityRef,
     pub comment: Markdown,
 }
 
-#[derive(Serialize, Deserialize)]
+impl Entity for Comment {
+    fn get_title(&self, id: &str) -> String {
+        self.title.clone().unwrap_or(id.to_string())
+    }
+    fn get_description(&self) -> String {
+        self.comment.clone()
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
 #[serde(deny_unknown_fields)]
 pub struct Model {
     pub title: Option<String>,
+    pub include: Option<Vec<String>>,
     pub actor: Option<IndexMap<String, Actor>>,
     pub agent: Option<IndexMap<String, Agent>>,
     pub application: Option<IndexMap<String, Application>>,
@@ -632,6 +1214,7 @@ pub struct Model {
     pub data: Option<IndexMap<String, Data>>,
     pub flow: Option<IndexMap<String, Flow>>,
     pub store: Option<IndexMap<String, Store>>,
+    pub backup: Option<IndexMap<String, Backup>>,
 
     pub network: Option<IndexMap<String, Network>>,
     pub server: Option<IndexMap<String, Server>>,
@@ -640,7 +1223,9 @@ pub struct Model {
     pub protocol: Option<IndexMap<String, Protocol>>,
 
     pub deployment: Option<IndexMap<String, Deployment>>,
+    pub boundary: Option<IndexMap<String, Boundary>>,
 
+    pub acceptance: Option<IndexMap<String, Acceptance>>,
     pub control: Option<IndexMap<String, Control>>,
     pub classification: Option<IndexMap<String, Classification>>,
     pub risk: Option<IndexMap<String, Risk>>,
@@ -650,344 +1235,321 @@ pub struct Model {
     pub authentication: Option<IndexMap<String, Authentication>>,
     pub authorization: Option<IndexMap<String, Authorization>>,
     pub encryption: Option<IndexMap<String, Encryption>>,
+    pub key: Option<IndexMap<String, Key>>,
+    pub secret: Option<IndexMap<String, Secret>>,
 
     pub comment: Option<Vec<Comment>>,
+
+    #[serde(skip)]
+    pub sources: SourceMap,
+
+    #[serde(skip)]
+    graph: OnceLock<ModelGraph>,
 }
 
 impl Model {
-    pub fn application_by_id<'a>(&'a self, id: &str) -> Option<&'a Application> {
-        if let Some(m) = &self.application {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
+    // look up an entity by ID, e.g. `model.lookup::<Store>("store.main-mysql")`
+    #[must_use]
+    pub fn lookup<T: Lookup>(&self, id: &str) -> Option<&T> {
+        let name = id.strip_prefix(T::KIND?)?.strip_prefix('.')?;
+        T::table(self)?.get(name)
+    }
+
+    // the relationships between entities, indexed the first time they are
+    // needed, so the model should not be changed after that
+    pub fn graph(&self) -> &ModelGraph {
+        self.graph.get_or_init(|| ModelGraph::new(self))
+    }
+
+    // the IDs of every entity in the model, other than comments, grouped
+    // by kind, in the order they are defined
+    pub fn entity_ids(&self) -> Vec<EntityRef> {
+        fn ids<T>(kind: &str, table: Option<&IndexMap<String, T>>) -> Vec<EntityRef> {
+            table
+                .into_iter()
+                .flat_map(IndexMap::keys)
+                .filter_map(|name| format!("{kind}.{name}").parse().ok())
+                .collect()
         }
+        [
+            ids("actor", self.actor.as_ref()),
+            ids("agent", self.agent.as_ref()),
+            ids("application", self.application.as_ref()),
+            ids("channel", self.channel.as_ref()),
+            ids("data", self.data.as_ref()),
+            ids("flow", self.flow.as_ref()),
+            ids("store", self.store.as_ref()),
+            ids("backup", self.backup.as_ref()),
+            ids("network", self.network.as_ref()),
+            ids("server", self.server.as_ref()),
+            ids("endpoint", self.endpoint.as_ref()),
+            ids("process", self.process.as_ref()),
+            ids("protocol", self.protocol.as_ref()),
+            ids("deployment", self.deployment.as_ref()),
+            ids("boundary", self.boundary.as_ref()),
+            ids("acceptance", self.acceptance.as_ref()),
+            ids("control", self.control.as_ref()),
+            ids("classification", self.classification.as_ref()),
+            ids("risk", self.risk.as_ref()),
+            ids("threat", self.threat.as_ref()),
+            ids("regulation", self.regulation.as_ref()),
+            ids("authentication", self.authentication.as_ref()),
+            ids("authorization", self.authorization.as_ref()),
+            ids("encryption", self.encryption.as_ref()),
+            ids("key", self.key.as_ref()),
+            ids("secret", self.secret.as_ref()),
+        ]
+        .concat()
     }
-    pub fn network_by_id<'a>(&'a self, id: &str) -> Option<&'a Network> {
-        if let Some(m) = &self.network {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+
+    // the file an entity was defined in, when the model was loaded from disk
+    #[must_use]
+    pub fn entity_file(&self, id: &str) -> Option<&str> {
+        self.sources.file_of(id)
     }
-    pub fn endpoint_by_id<'a>(&'a self, id: &str) -> Option<&'a Endpoint> {
-        if let Some(m) = &self.endpoint {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+
+    #[must_use]
+    pub fn application_by_id(&self, id: &str) -> Option<&Application> {
+        self.lookup(id)
+    }
+    #[must_use]
+    pub fn network_by_id(&self, id: &str) -> Option<&Network> {
+        self.lookup(id)
+    }
+    #[must_use]
+    pub fn endpoint_by_id(&self, id: &str) -> Option<&Endpoint> {
+        self.lookup(id)
     }
 
-    pub fn server_by_id<'a>(&'a self, id: &str) -> Option<&'a Server> {
-        if let Some(m) = &self.server {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+    #[must_use]
+    pub fn server_by_id(&self, id: &str) -> Option<&Server> {
+        self.lookup(id)
     }
 
-    pub fn actor_by_id<'a>(&'a self, id: &str) -> Option<&'a Actor> {
-        if let Some(m) = &self.actor {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+    #[must_use]
+    pub fn actor_by_id(&self, id: &str) -> Option<&Actor> {
+        self.lookup(id)
     }
 
-    pub fn flow_by_id<'a>(&'a self, id: &str) -> Option<&'a Flow> {
-        if let Some(m) = &self.flow {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+    #[must_use]
+    pub fn flow_by_id(&self, id: &str) -> Option<&Flow> {
+        self.lookup(id)
     }
 
-    pub fn agent_by_id<'a>(&'a self, id: &str) -> Option<&'a Agent> {
-        if let Some(m) = &self.agent {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+    #[must_use]
+    pub fn agent_by_id(&self, id: &str) -> Option<&Agent> {
+        self.lookup(id)
     }
 
-    pub fn store_by_id<'a>(&'a self, id: &str) -> Option<&'a Store> {
-        if let Some(m) = &self.store {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+    #[must_use]
+    pub fn store_by_id(&self, id: &str) -> Option<&Store> {
+        self.lookup(id)
     }
 
-    pub fn risk_by_id<'a>(&'a self, id: &str) -> Option<&'a Risk> {
-        if let Some(m) = &self.risk {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+    #[must_use]
+    pub fn risk_by_id(&self, id: &str) -> Option<&Risk> {
+        self.lookup(id)
     }
 
-    pub fn data_by_id<'a>(&'a self, id: &str) -> Option<&'a Data> {
-        if let Some(m) = &self.data {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+    #[must_use]
+    pub fn data_by_id(&self, id: &str) -> Option<&Data> {
+        self.lookup(id)
     }
 
-    pub fn channel_by_id<'a>(&'a self, id: &str) -> Option<&'a Channel> {
-        if let Some(m) = &self.channel {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+    #[must_use]
+    pub fn channel_by_id(&self, id: &str) -> Option<&Channel> {
+        self.lookup(id)
     }
 
-    pub fn authentication_by_id<'a>(&'a self, id: &str) -> Option<&'a Authentication> {
-        if let Some(m) = &self.authentication {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+    #[must_use]
+    pub fn authentication_by_id(&self, id: &str) -> Option<&Authentication> {
+        self.lookup(id)
     }
 
-    pub fn authorization_by_id<'a>(&'a self, id: &str) -> Option<&'a Authorization> {
-        if let Some(m) = &self.authorization {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+    #[must_use]
+    pub fn authorization_by_id(&self, id: &str) -> Option<&Authorization> {
+        self.lookup(id)
     }
 
-    pub fn encryption_by_id<'a>(&'a self, id: &str) -> Option<&'a Encryption> {
-        if let Some(m) = &self.encryption {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+    pub fn key_by_id(&self, id: &str) -> Option<&Key> {
+        self.lookup(id)
     }
 
-    pub fn process_by_id<'a>(&'a self, id: &str) -> Option<&'a Process> {
-        if let Some(m) = &self.process {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+    pub fn secret_by_id(&self, id: &str) -> Option<&Secret> {
+        self.lookup(id)
     }
 
-    pub fn protocol_by_id<'a>(&'a self, id: &str) -> Option<&'a Protocol> {
-        if let Some(m) = &self.protocol {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+    #[must_use]
+    pub fn encryption_by_id(&self, id: &str) -> Option<&Encryption> {
+        self.lookup(id)
     }
 
-    pub fn regulation_by_id<'a>(&'a self, id: &str) -> Option<&'a Regulation> {
-        if let Some(m) = &self.regulation {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+    #[must_use]
+    pub fn process_by_id(&self, id: &str) -> Option<&Process> {
+        self.lookup(id)
     }
 
-    pub fn control_by_id<'a>(&'a self, id: &str) -> Option<&'a Control> {
-        if let Some(m) = &self.control {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+    #[must_use]
+    pub fn protocol_by_id(&self, id: &str) -> Option<&Protocol> {
+        self.lookup(id)
     }
 
-    pub fn classification_by_id<'a>(&'a self, id: &str) -> Option<&'a Classification> {
-        if let Some(m) = &self.classification {
-            let parts: Vec<&str> = id.split(".").collect();
-            m.get(parts[1])
-        } else {
-            None
-        }
+    #[must_use]
+    pub fn regulation_by_id(&self, id: &str) -> Option<&Regulation> {
+        self.lookup(id)
     }
 
-    pub fn entity_by_id<'a>(&'a self, id: &str) -> Option<Box<&dyn Entity>> {
-        let eid = EntityID::from_str(id).unwrap();
-        match eid {
-            EntityID::Application(..) => self
-                .application_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Actor(..) => self
-                .actor_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Agent(..) => self
-                .agent_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Server(..) => self
-                .server_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Network(..) => self
-                .network_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Endpoint(..) => self
-                .endpoint_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Process(..) => self
-                .process_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Protocol(..) => self
-                .protocol_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Data(..) => self
-                .data_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Store(..) => self
-                .store_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Flow(..) => self
-                .flow_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Channel(..) => self
-                .channel_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Risk(..) => self
-                .risk_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Control(..) => self
-                .control_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Classification(..) => self
-                .classification_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Regulation(..) => self
-                .regulation_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Authorization(..) => self
-                .authorization_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Authentication(..) => self
-                .authentication_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Encryption(..) => self
-                .encryption_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-            EntityID::Threat(..) => self
-                .flow_by_id(id)
-                .and_then(|a| Some(Box::new(a as &dyn Entity))),
-        }
+    #[must_use]
+    pub fn control_by_id(&self, id: &str) -> Option<&Control> {
+        self.lookup(id)
     }
 
-    pub fn network_servers<'a>(&self, network_id: &str) -> Vec<String> {
-        let mut my_servers = Vec::new();
-        if let Some(servers) = &self.server {
-            for (serverid, server) in servers {
-                if let Some(interfaces) = &server.interfaces {
-                    if interfaces.len() == 1 {
-                        for i in interfaces {
-                            if let Some(network) = &i.network {
-                                if network == network_id {
-                                    let sid = format!("server.{serverid}");
-                                    my_servers.push(sid)
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        my_servers
-    }
-    pub fn network_connected_servers<'a>(&self, network_id: &str) -> Vec<String> {
-        let mut my_servers = Vec::new();
-        if let Some(servers) = &self.server {
-            for (serverid, server) in servers {
-                if let Some(interfaces) = &server.interfaces {
-                    for i in interfaces {
-                        if let Some(network) = &i.network {
-                            if network == network_id {
-                                let sid = format!("server.{serverid}");
-                                my_servers.push(sid)
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        my_servers
+    #[must_use]
+    pub fn classification_by_id(&self, id: &str) -> Option<&Classification> {
+        self.lookup(id)
+    }
+
+    #[must_use]
+    pub fn deployment_by_id(&self, id: &str) -> Option<&Deployment> {
+        self.lookup(id)
+    }
+
+    pub fn backup_by_id(&self, id: &str) -> Option<&Backup> {
+        self.lookup(id)
+    }
+
+    pub fn boundary_by_id(&self, id: &str) -> Option<&Boundary> {
+        self.lookup(id)
+    }
+
+    pub fn acceptance_by_id(&self, id: &str) -> Option<&Acceptance> {
+        self.lookup(id)
+    }
+
+    #[must_use]
+    pub fn threat_by_id(&self, id: &str) -> Option<&Threat> {
+        self.lookup(id)
+    }
+
+    // comments have no name, so they are identified by their position in
+    // the model, e.g. `comment.0`
+    #[must_use]
+    pub fn comment_by_id(&self, id: &str) -> Option<&Comment> {
+        let n: usize = id.strip_prefix("comment.")?.parse().ok()?;
+        self.comment.as_ref()?.get(n)
+    }
+
+    #[allow(clippy::missing_errors_doc)]
+    pub fn entity_by_id(&self, id: &str) -> Result<&dyn Entity, LookupError> {
+        let eid =
+            EntityID::from_str(id).map_err(|_| LookupError::UnknownEntityType(id.to_string()))?;
+        let entity: Option<&dyn Entity> = match eid {
+            EntityID::Application(..) => self.application_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Actor(..) => self.actor_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Agent(..) => self.agent_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Server(..) => self.server_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Network(..) => self.network_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Endpoint(..) => self.endpoint_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Process(..) => self.process_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Protocol(..) => self.protocol_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Data(..) => self.data_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Store(..) => self.store_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Flow(..) => self.flow_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Channel(..) => self.channel_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Risk(..) => self.risk_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Control(..) => self.control_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Classification(..) => self.classification_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Regulation(..) => self.regulation_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Authorization(..) => self.authorization_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Authentication(..) => self.authentication_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Encryption(..) => self.encryption_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Key(..) => self.key_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Secret(..) => self.secret_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Threat(..) => self.threat_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Deployment(..) => self.deployment_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Backup(..) => self.backup_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Boundary(..) => self.boundary_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Acceptance(..) => self.acceptance_by_id(id).map(|a| a as &dyn Entity),
+            EntityID::Comment(..) => self.comment_by_id(id).map(|a| a as &dyn Entity),
+        };
+        entity.ok_or_else(|| LookupError::NotFound(id.to_string()))
+    }
+
+    // those servers connected to *only* this network
+    #[must_use]
+    pub fn network_servers(&self, network_id: &str) -> Vec<ServerID> {
+        self.network_members(network_id, true)
+    }
+    #[must_use]
+    pub fn network_connected_servers(&self, network_id: &str) -> Vec<ServerID> {
+        self.network_members(network_id, false)
     }
     // those endpoints connected to *only* this network
-    pub fn network_endpoints<'a>(&self, network_id: &str) -> Vec<String> {
-        let mut my_endpoints = Vec::new();
-        if let Some(endpoints) = &self.endpoint {
-            for (endpointid, endpoint) in endpoints {
-                if let Some(interfaces) = &endpoint.interfaces {
-                    if interfaces.len() == 1 {
-                        for i in interfaces {
-                            if let Some(network) = &i.network {
-                                if network == network_id {
-                                    let sid = format!("endpoint.{endpointid}");
-                                    my_endpoints.push(sid)
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        my_endpoints
+    #[must_use]
+    pub fn network_endpoints(&self, network_id: &str) -> Vec<EndpointID> {
+        self.network_members(network_id, true)
     }
     // those endpoints connected to this network
-    pub fn network_connected_endpoints<'a>(&self, network_id: &str) -> Vec<String> {
-        let mut my_endpoints = Vec::new();
-        if let Some(endpoints) = &self.endpoint {
-            for (endpointid, endpoint) in endpoints {
-                if let Some(interfaces) = &endpoint.interfaces {
-                    for i in interfaces {
-                        if let Some(network) = &i.network {
-                            if network == network_id {
-                                let sid = format!("endpoint.{endpointid}");
-                                my_endpoints.push(sid)
-                            }
-                        }
-                    }
-                }
+    #[must_use]
+    pub fn network_connected_endpoints(&self, network_id: &str) -> Vec<EndpointID> {
+        self.network_members(network_id, false)
+    }
+
+    // the network with the narrowest range containing the address
+    pub fn network_containing(&self, address: &IpAddr) -> Option<NetworkID> {
+        self.network
+            .iter()
+            .flatten()
+            .flat_map(|(name, network)| network.ranges().into_iter().map(move |r| (name, r)))
+            .filter(|(_, range)| range.contains(address))
+            .max_by_key(|(_, range)| range.prefix_len())
+            .map(|(name, _)| NetworkID::named(name))
+    }
+
+    fn network_members<K: EntityKind>(&self, network_id: &str, only: bool) -> Vec<EntityRef<K>> {
+        let graph = self.graph();
+        let mut members: Vec<EntityRef<K>> = Vec::new();
+        for id in graph.reverse_neighbors(network_id, Relation::ConnectsTo) {
+            let Some(member) = id.downcast::<K>() else {
+                continue;
+            };
+            let interfaces = graph.neighbors(id, Relation::ConnectsTo).len();
+            if (!only || interfaces == 1) && !members.contains(&member) {
+                members.push(member);
             }
         }
-        my_endpoints
-    }
-
-    pub fn data_stores(&self, data_id: &str) -> Vec<String> {
-        let target = data_id.to_string();
-        if let Some(stores) = &self.store {
-            stores
-                .iter()
-                .filter_map(|(sid, store)| {
-                    if store.data.clone().unwrap_or(Vec::new()).contains(&target) {
-                        Some(format!("store.{sid}"))
-                    } else {
-                        None
-                    }
-                })
-                .collect()
-        } else {
-            Vec::new()
-        }
+        members
+    }
+
+    // the applications a server or endpoint runs: those it lists, then
+    // those deployments put on it
+    #[must_use]
+    pub fn host_applications(&self, host_id: &str) -> Vec<ApplicationID> {
+        self.hosted(host_id)
     }
 
-    pub fn entity_comments<'a>(&self, entity_id: &str) -> Vec<&Comment> {
+    // the stores a server or endpoint runs, as for applications
+    #[must_use]
+    pub fn host_stores(&self, host_id: &str) -> Vec<StoreID> {
+        self.hosted(host_id)
+    }
+
+    fn hosted<K: EntityKind>(&self, host_id: &str) -> Vec<EntityRef<K>> {
+        let hosted = self.graph().hosted(host_id);
+        hosted.into_iter().filter_map(EntityRef::downcast).collect()
+    }
+
+    #[must_use]
+    pub fn data_stores(&self, data_id: &str) -> Vec<StoreID> {
+        self.graph()
+            .reverse_neighbors(data_id, Relation::StoresData)
+            .into_iter()
+            .filter_map(EntityRef::downcast)
+            .collect()
+    }
+
+    #[must_use]
+    pub fn entity_comments(&self, entity_id: &str) -> Vec<&Comment> {
         if let Some(comments) = &self.comment {
             comments.iter().filter(|c| c.entity == entity_id).collect()
         } else {
@@ -995,30 +1557,34 @@ impl Model {
         }
     }
 
-    pub fn entity_flows(&self, entity_id: &str) -> Vec<String> {
-        let target = entity_id.to_string();
-        if let Some(flows) = &self.flow {
-            flows
-                .iter()
-                .filter_map(|(fid, flow)| {
-                    if flow.sources.clone().unwrap_or(Vec::new()).contains(&target)
-                        || flow
-                            .destinations
-                            .clone()
-                            .unwrap_or(Vec::new())
-                            .contains(&target)
-                        || flow.data.clone().unwrap_or(Vec::new()).contains(&target)
-                        || flow.channel.clone().unwrap_or(String::new()) == target
-                    {
-                        Some(format!("flow.{fid}"))
-                    } else {
-                        None
-                    }
-                })
-                .collect()
-        } else {
-            Vec::new()
-        }
+    // a channel, then the channels it is carried over, nearest first
+    #[must_use]
+    pub fn channel_stack(&self, channel_id: &str) -> Vec<&Channel> {
+        let over = self.graph().reachable(channel_id, &[Relation::Uses]);
+        let over = over.into_iter().filter(|c| c.kind() == "channel");
+        std::iter::once(channel_id)
+            .chain(over.map(EntityRef::as_str))
+            .filter_map(|c| self.channel_by_id(c))
+            .collect()
+    }
+
+    pub fn entity_flows(&self, entity_id: &str) -> Vec<FlowID> {
+        let graph = self.graph();
+        let mut flows: Vec<FlowID> = graph
+            .outgoing(entity_id)
+            .filter(|e| e.relation == Relation::FlowsTo)
+            .map(|e| &e.to)
+            .chain(graph.incoming(entity_id).filter_map(|e| match e.relation {
+                Relation::FlowsTo | Relation::CarriesData | Relation::Uses => Some(&e.from),
+                _ => None,
+            }))
+            .filter_map(EntityRef::downcast)
+            .collect();
+        // in the order the model defines them
+        let order = |f: &FlowID| self.flow.as_ref().and_then(|m| m.get_index_of(f.name()));
+        flows.sort_by_key(order);
+        flows.dedup();
+        flows
     }
 }
 
@@ -1027,21 +1593,11 @@ pub enum ModelError {
     #[error("Error reading model: {0}")]
     ReadError(String),
     #[error("Error parsing model: {0}")]
-    ParseError(String),
+    ParseError(Box<Diagnostic>),
     #[error("Error Compiling model: {0}")]
     CompileError(String),
-}
-
-pub fn load(path: &str) -> Result<Model, ModelError> {
-    let modelsrc = match fs::read_to_string(path) {
-        Ok(m) => Ok(m),
-        Err(e) => Err(ModelError::ReadError(e.to_string())),
-    }?;
-
-    match toml::from_str(&modelsrc) {
-        Ok(m) => Ok(m),
-        Err(e) => Err(ModelError::ParseError(e.to_string())),
-    }
+    #[error("Error merging model: {} duplicate entities", .0.len())]
+    MergeError(Vec<Diagnostic>),
 }
 
 #[cfg(test)]
@@ -1052,4 +1608,36 @@ mod tests {
     fn it_works() {
         load("../test/test_model.toml").unwrap();
     }
+
+    #[test]
+    fn entity_lookup() {
+        let model: Model = toml::from_str(
+            r#"
+[threat.phishing]
+title = "Phishing"
+[deployment.web]
+[[comment]]
+entity = "threat.phishing"
+comment = "Seen in the wild"
+"#,
+        )
+        .unwrap();
+        let threat = model.entity_by_id("threat.phishing").unwrap();
+        assert_eq!("Phishing", threat.get_title("threat.phishing"));
+        assert!(model.entity_by_id("deployment.web").is_ok());
+        let comment = model.entity_by_id("comment.0").unwrap();
+        assert_eq!("Seen in the wild", comment.get_description());
+        assert!(matches!(
+            model.entity_by_id("threat.missing"),
+            Err(LookupError::NotFound(_))
+        ));
+        assert!(matches!(
+            model.entity_by_id("widget.foo"),
+            Err(LookupError::UnknownEntityType(_))
+        ));
+        assert!(matches!(
+            model.entity_by_id("no-dot"),
+            Err(LookupError::UnknownEntityType(_))
+        ));
+    }
 }
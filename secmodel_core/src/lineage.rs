@@ -0,0 +1,187 @@
+use crate::{
+    AgentID, ApplicationID, DataID, EndpointID, EntityKind, EntityRef, FlowID, LookupError, Model,
+    NetworkID, Relation, ServerID, StoreID,
+};
+use indexmap::IndexSet;
+use serde::Serialize;
+use std::fmt;
+
+// Everywhere a type of data travels to, or rests in.
+#[derive(Serialize, Debug)]
+pub struct Lineage {
+    pub data: DataID,
+    // data which contains this data, and so carries it wherever it goes
+    pub containers: Vec<DataID>,
+    pub flows: Vec<FlowID>,
+    pub applications: Vec<ApplicationID>,
+    pub stores: Vec<StoreID>,
+    pub agents: Vec<AgentID>,
+    pub endpoints: Vec<EndpointID>,
+    pub servers: Vec<ServerID>,
+    pub networks: Vec<NetworkID>,
+}
+
+// Follow a type of data through the model: the flows that carry it, or
+// data containing it, the sources and destinations of those flows, the
+// stores holding it and their backing stores, then the servers and
+// endpoints hosting all of those, and the networks they are connected to.
+#[allow(clippy::missing_errors_doc)]
+pub fn lineage(model: &Model, data_id: &str) -> Result<Lineage, LookupError> {
+    if model.data_by_id(data_id).is_none() {
+        return Err(LookupError::NotFound(data_id.to_string()));
+    }
+    let graph = model.graph();
+
+    let data: IndexSet<&str> = std::iter::once(data_id)
+        .chain(
+            graph
+                .reverse_reachable(data_id, &[Relation::Contains])
+                .into_iter()
+                .map(EntityRef::as_str),
+        )
+        .collect();
+
+    let mut flows: IndexSet<&str> = IndexSet::new();
+    let mut touched: IndexSet<&str> = IndexSet::new();
+    for d in &data {
+        for id in graph.reverse_neighbors(d, Relation::CarriesData) {
+            flows.insert(id.as_str());
+        }
+        for id in graph.reverse_neighbors(d, Relation::StoresData) {
+            touched.insert(id.as_str());
+        }
+    }
+    for flow in &flows {
+        for id in graph.reverse_neighbors(flow, Relation::FlowsTo) {
+            touched.insert(id.as_str());
+        }
+        for id in graph.neighbors(flow, Relation::FlowsTo) {
+            touched.insert(id.as_str());
+        }
+    }
+
+    // copies of the data end up in backing stores too
+    let stores: Vec<&str> = touched
+        .iter()
+        .copied()
+        .filter(|id| id.parse::<StoreID>().is_ok())
+        .collect();
+    for store in stores {
+        for id in graph.reachable(store, &[Relation::BackedBy]) {
+            touched.insert(id.as_str());
+        }
+    }
+
+    // the hosts of what was touched, directly or through a deployment
+    let hosted: Vec<&str> = touched.iter().copied().collect();
+    for id in hosted {
+        for host in graph.hosts(id) {
+            touched.insert(host.as_str());
+        }
+    }
+
+    let connected: Vec<&str> = touched.iter().copied().collect();
+    for id in connected {
+        for network in graph.neighbors(id, Relation::ConnectsTo) {
+            touched.insert(network.as_str());
+        }
+    }
+
+    Ok(Lineage {
+        data: DataID::named(data_id.strip_prefix("data.").unwrap_or(data_id)),
+        containers: of_kind(data.iter().skip(1)),
+        flows: of_kind(flows.iter()),
+        applications: of_kind(touched.iter()),
+        stores: of_kind(touched.iter()),
+        agents: of_kind(touched.iter()),
+        endpoints: of_kind(touched.iter()),
+        servers: of_kind(touched.iter()),
+        networks: of_kind(touched.iter()),
+    })
+}
+
+impl Lineage {
+    // every entity the data touches, other than the flows carrying it
+    pub fn entities(&self) -> impl Iterator<Item = (&'static str, Vec<&str>)> {
+        [
+            ("Applications", refs(&self.applications)),
+            ("Stores", refs(&self.stores)),
+            ("Agents", refs(&self.agents)),
+            ("Endpoints", refs(&self.endpoints)),
+            ("Servers", refs(&self.servers)),
+            ("Networks", refs(&self.networks)),
+        ]
+        .into_iter()
+    }
+}
+
+impl fmt::Display for Lineage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.data)?;
+        let lists = [
+            ("Contained in", refs(&self.containers)),
+            ("Flows", refs(&self.flows)),
+        ];
+        for (label, ids) in lists.into_iter().chain(self.entities()) {
+            if !ids.is_empty() {
+                writeln!(f, "  {label}:")?;
+                for id in ids {
+                    writeln!(f, "    {id}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn refs<K>(ids: &[EntityRef<K>]) -> Vec<&str> {
+    ids.iter().map(EntityRef::as_str).collect()
+}
+
+// those IDs which refer to entities of the kind `K`
+fn of_kind<'a, K: EntityKind>(ids: impl Iterator<Item = &'a &'a str>) -> Vec<EntityRef<K>> {
+    ids.filter_map(|id| id.parse().ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::load;
+
+    #[test]
+    fn health_info() {
+        let model = load("../test/test_model.toml").unwrap();
+        let l = lineage(&model, "data.health-info").unwrap();
+        assert_eq!(l.flows, vec!["flow.mainapp-backend", "flow.mainapp-client"]);
+        assert_eq!(l.stores, vec!["store.main-mysql", "store.prod-fs"]);
+        assert_eq!(l.applications, vec!["application.mainapp"]);
+        assert_eq!(l.agents, vec!["agent.client-browser"]);
+        assert_eq!(l.endpoints, vec!["endpoint.client-computer"]);
+        assert_eq!(l.servers, vec!["server.prod"]);
+        assert_eq!(l.networks, vec!["network.prod", "network.internet"]);
+
+        assert!(matches!(
+            lineage(&model, "data.missing"),
+            Err(LookupError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn contained_data() {
+        let model: Model = toml::from_str(
+            r#"
+[data.record]
+contains = ["data.ssn"]
+[data.ssn]
+[store.archive]
+[store.db]
+data = ["data.record"]
+backing = "store.archive"
+"#,
+        )
+        .unwrap();
+        let l = lineage(&model, "data.ssn").unwrap();
+        assert_eq!(l.containers, vec!["data.record"]);
+        assert_eq!(l.stores, vec!["store.db", "store.archive"]);
+    }
+}
@@ -0,0 +1,302 @@
+use crate::{Diagnostic, Location, Model, ModelError, Severity};
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Load a model from a file, or from every `*.toml` file in a directory.
+// Files may pull in further fragments with a top level
+// `include = ["networks/*.toml", "data.toml"]`, resolved relative to the
+// including file.  Entities are merged into a single model, and an entity
+// defined in more than one file is an error.
+#[allow(clippy::missing_errors_doc)]
+pub fn load(path: &str) -> Result<Model, ModelError> {
+    let mut loader = Loader::default();
+    let mut model = Model::default();
+    if Path::new(path).is_dir() {
+        for file in expand(Path::new(path), "*.toml")? {
+            loader.load_file(&mut model, &file)?;
+        }
+    } else {
+        loader.load_file(&mut model, Path::new(path))?;
+    }
+    if loader.duplicates.is_empty() {
+        Ok(model)
+    } else {
+        Err(ModelError::MergeError(loader.duplicates))
+    }
+}
+
+#[derive(Default)]
+struct Loader {
+    loaded: HashSet<PathBuf>,
+    duplicates: Vec<Diagnostic>,
+}
+
+impl Loader {
+    fn load_file(&mut self, model: &mut Model, path: &Path) -> Result<(), ModelError> {
+        let canonical = path
+            .canonicalize()
+            .map_err(|e| ModelError::ReadError(format!("{}: {e}", path.display())))?;
+        if !self.loaded.insert(canonical) {
+            return Ok(());
+        }
+        let fragment = parse(&path.to_string_lossy())?;
+        let includes = fragment.include.clone().unwrap_or_default();
+        self.merge(model, fragment);
+
+        let base = path.parent().unwrap_or(Path::new(""));
+        for pattern in &includes {
+            let files = expand(base, pattern)?;
+            if files.is_empty() {
+                return Err(ModelError::ReadError(format!(
+                    "{}: include `{pattern}` matched no files",
+                    path.display()
+                )));
+            }
+            for file in files {
+                self.load_file(model, &file)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn merge(&mut self, model: &mut Model, fragment: Model) {
+        let Model {
+            title,
+            include,
+            actor,
+            agent,
+            application,
+            channel,
+            data,
+            flow,
+            store,
+            backup,
+            network,
+            server,
+            endpoint,
+            process,
+            protocol,
+            deployment,
+            boundary,
+            acceptance,
+            control,
+            classification,
+            risk,
+            threat,
+            regulation,
+            authentication,
+            authorization,
+            encryption,
+            key,
+            secret,
+            comment,
+            sources,
+            graph: _,
+        } = fragment;
+
+        let mut ids = Vec::new();
+        if model.title.is_none() {
+            model.title = title;
+        }
+        if model.include.is_none() {
+            model.include = include;
+        }
+        merge_map("actor", &mut model.actor, actor, &mut ids);
+        merge_map("agent", &mut model.agent, agent, &mut ids);
+        merge_map("application", &mut model.application, application, &mut ids);
+        merge_map("channel", &mut model.channel, channel, &mut ids);
+        merge_map("data", &mut model.data, data, &mut ids);
+        merge_map("flow", &mut model.flow, flow, &mut ids);
+        merge_map("store", &mut model.store, store, &mut ids);
+        merge_map("backup", &mut model.backup, backup, &mut ids);
+        merge_map("network", &mut model.network, network, &mut ids);
+        merge_map("server", &mut model.server, server, &mut ids);
+        merge_map("endpoint", &mut model.endpoint, endpoint, &mut ids);
+        merge_map("process", &mut model.process, process, &mut ids);
+        merge_map("protocol", &mut model.protocol, protocol, &mut ids);
+        merge_map("deployment", &mut model.deployment, deployment, &mut ids);
+        merge_map("boundary", &mut model.boundary, boundary, &mut ids);
+        merge_map("acceptance", &mut model.acceptance, acceptance, &mut ids);
+        merge_map("control", &mut model.control, control, &mut ids);
+        merge_map(
+            "classification",
+            &mut model.classification,
+            classification,
+            &mut ids,
+        );
+        merge_map("risk", &mut model.risk, risk, &mut ids);
+        merge_map("threat", &mut model.threat, threat, &mut ids);
+        merge_map("regulation", &mut model.regulation, regulation, &mut ids);
+        merge_map(
+            "authentication",
+            &mut model.authentication,
+            authentication,
+            &mut ids,
+        );
+        merge_map(
+            "authorization",
+            &mut model.authorization,
+            authorization,
+            &mut ids,
+        );
+        merge_map("encryption", &mut model.encryption, encryption, &mut ids);
+        merge_map("key", &mut model.key, key, &mut ids);
+        merge_map("secret", &mut model.secret, secret, &mut ids);
+
+        for id in ids {
+            let first = model
+                .sources
+                .locate(&id)
+                .map_or_else(String::new, |l| format!(", first defined at {l}"));
+            self.duplicates.push(Diagnostic {
+                severity: Severity::Error,
+                entity: id.clone(),
+                field: String::new(),
+                message: format!("duplicate definition of `{id}`{first}"),
+                location: sources.locate(&id),
+            });
+        }
+
+        if let Some(comments) = comment {
+            let offset = model.comment.as_ref().map_or(0, Vec::len);
+            model.sources.extend(sources, |key| {
+                renumber_comment(key, offset).unwrap_or_else(|| key.to_string())
+            });
+            model.comment.get_or_insert_with(Vec::new).extend(comments);
+        } else {
+            model.sources.extend(sources, str::to_string);
+        }
+    }
+}
+
+// move the entries of `from` into `into`, keeping the first definition of
+// any entity defined in both, and recording its ID in `duplicates`
+fn merge_map<T>(
+    kind: &str,
+    into: &mut Option<IndexMap<String, T>>,
+    from: Option<IndexMap<String, T>>,
+    duplicates: &mut Vec<String>,
+) {
+    let Some(from) = from else {
+        return;
+    };
+    let into = into.get_or_insert_with(IndexMap::new);
+    for (name, entity) in from {
+        if into.contains_key(&name) {
+            duplicates.push(format!("{kind}.{name}"));
+        } else {
+            into.insert(name, entity);
+        }
+    }
+}
+
+// comments are indexed by position, so shift those of a fragment past the
+// comments already loaded, e.g. `comment[0].entity` -> `comment[3].entity`
+fn renumber_comment(key: &str, offset: usize) -> Option<String> {
+    let rest = key.strip_prefix("comment[")?;
+    let (n, rest) = rest.split_once(']')?;
+    let n: usize = n.parse().ok()?;
+    Some(format!("comment[{}]{rest}", n + offset))
+}
+
+fn expand(base: &Path, pattern: &str) -> Result<Vec<PathBuf>, ModelError> {
+    let pattern = base.join(pattern);
+    let pattern = pattern.to_string_lossy();
+    let paths =
+        glob::glob(&pattern).map_err(|e| ModelError::ReadError(format!("{pattern}: {e}")))?;
+    let mut files = Vec::new();
+    for p in paths {
+        files.push(p.map_err(|e| ModelError::ReadError(e.to_string()))?);
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn parse(path: &str) -> Result<Model, ModelError> {
+    let (mut model, modelsrc): (Model, String) = parse_file(path)?;
+    model.sources.add(path, modelsrc);
+    Ok(model)
+}
+
+// read and deserialize a TOML file, returning its text too
+pub(crate) fn parse_file<T: DeserializeOwned>(path: &str) -> Result<(T, String), ModelError> {
+    let src = match fs::read_to_string(path) {
+        Ok(m) => Ok(m),
+        Err(e) => Err(ModelError::ReadError(format!("{path}: {e}"))),
+    }?;
+
+    match toml::from_str(&src) {
+        Ok(t) => Ok((t, src)),
+        Err(e) => Err(ModelError::ParseError(Box::new(Diagnostic {
+            severity: Severity::Error,
+            entity: String::new(),
+            field: String::new(),
+            message: e.message().to_string(),
+            location: e.span().map(|s| Location::from_span(path, &src, &s)),
+        }))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, text: &str) {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("secmodel-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn include_fragments() {
+        let dir = scratch("include");
+        write(
+            &dir,
+            "model.toml",
+            "include = [\"networks/*.toml\"]\n[server.web]\ninterfaces = [{network=\"network.dmz\"}]\n",
+        );
+        write(&dir, "networks/dmz.toml", "[network.dmz]\ntitle = \"DMZ\"\n\n[[comment]]\nentity = \"network.dmz\"\ncomment = \"hi\"\n");
+        write(&dir, "networks/lan.toml", "[network.lan]\n");
+
+        let m = load(&dir.join("model.toml").to_string_lossy()).unwrap();
+        assert_eq!(2, m.network.as_ref().unwrap().len());
+        assert!(crate::validate(&m).is_empty());
+        let file = m.entity_file("network.dmz").unwrap();
+        assert!(file.ends_with("networks/dmz.toml"));
+        assert!(m.entity_file("server.web").unwrap().ends_with("model.toml"));
+        let l = m.sources.locate("comment[0].entity").unwrap();
+        assert!(l.file.ends_with("networks/dmz.toml"));
+
+        // loading the directory picks up every file once
+        let m = load(&dir.join("networks").to_string_lossy()).unwrap();
+        assert_eq!(2, m.network.as_ref().unwrap().len());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn duplicate_ids() {
+        let dir = scratch("duplicate");
+        write(&dir, "a.toml", "[network.dmz]\n[risk.r]\n");
+        write(&dir, "b.toml", "[network.dmz]\ntitle = \"again\"\n");
+
+        let Err(ModelError::MergeError(diagnostics)) = load(&dir.to_string_lossy()) else {
+            panic!("expected a merge error");
+        };
+        assert_eq!(1, diagnostics.len());
+        assert_eq!("network.dmz", diagnostics[0].entity);
+        let l = diagnostics[0].location.as_ref().unwrap();
+        assert!(l.file.ends_with("b.toml"));
+        assert!(diagnostics[0].message.contains("a.toml"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,337 @@
+use crate::load::parse_file;
+use crate::{EntityID, EntityRef, Finding, Model, ModelError, Relation, Rule, Severity};
+use indexmap::IndexMap;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+// Organisation specific rules, declared in a TOML file of `[[rule]]`
+// tables, e.g.
+//
+//     [[rule]]
+//     name = "phi-stores-protected"
+//     applies_to = "store"
+//     when.related = [{ path = ["stores-data", "contains*", "regulated-by"],
+//                       to = "regulation.hipaa-phi" }]
+//     require.has = ["backup", "encryption"]
+//
+// A rule is checked against each entity of the type it applies to which
+// meets its `when` condition, and the entity passes when it meets the
+// `require` condition.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Policy {
+    rule: Vec<PolicyRule>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyRule {
+    pub name: String,
+    pub description: Option<String>,
+    pub severity: Option<Severity>,
+    pub applies_to: String,
+    pub when: Option<Condition>,
+    pub require: Condition,
+}
+
+// Every part of a condition given must hold.  Fields are named as they are
+// in the model, and a field which is missing, or empty, is not set.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Condition {
+    // fields which must be set
+    pub has: Option<Vec<String>>,
+    // fields which must not be set
+    pub lacks: Option<Vec<String>>,
+    // fields which must have the given values
+    pub equals: Option<IndexMap<String, toml::Value>>,
+    // relationships which must exist
+    pub related: Option<Vec<Related>>,
+}
+
+// A chain of relationships from the entity, which must reach `to`, or when
+// no `to` is given, any entity at all.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Related {
+    pub path: Vec<Step>,
+    pub to: Option<EntityRef>,
+}
+
+// A step along a path: the name of a relation, e.g. `stores-data`, with a
+// `~` prefix to follow it backwards, and a `*` suffix to follow it any
+// number of times, including none.
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(try_from = "String")]
+pub struct Step {
+    pub relation: Relation,
+    pub reverse: bool,
+    pub repeat: bool,
+}
+
+impl TryFrom<String> for Step {
+    type Error = String;
+
+    fn try_from(step: String) -> Result<Step, String> {
+        let (reverse, rest) = match step.strip_prefix('~') {
+            Some(rest) => (true, rest),
+            None => (false, step.as_str()),
+        };
+        let (repeat, name) = match rest.strip_suffix('*') {
+            Some(name) => (true, name),
+            None => (false, rest),
+        };
+        Ok(Step {
+            relation: Relation::from_str(name)?,
+            reverse,
+            repeat,
+        })
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reverse = if self.reverse { "~" } else { "" };
+        let repeat = if self.repeat { "*" } else { "" };
+        write!(f, "{reverse}{}{repeat}", self.relation)
+    }
+}
+
+// The result of checking a rule against one entity.
+#[derive(Debug)]
+pub struct Outcome {
+    pub entity: EntityRef,
+    // why the entity does not meet the rule, empty when it passes
+    pub failures: Vec<String>,
+}
+
+impl Outcome {
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+#[allow(clippy::missing_errors_doc)]
+pub fn load_policy(path: &str) -> Result<Vec<PolicyRule>, ModelError> {
+    let (policy, _): (Policy, String) = parse_file(path)?;
+    for rule in &policy.rule {
+        if EntityID::from_str(&format!("{}.x", rule.applies_to)).is_err() {
+            return Err(ModelError::CompileError(format!(
+                "{path}: rule `{}` applies to unknown entity type `{}`",
+                rule.name, rule.applies_to
+            )));
+        }
+    }
+    Ok(policy.rule)
+}
+
+impl PolicyRule {
+    // check the rule against every entity it applies to
+    pub fn outcomes(&self, model: &Model) -> Vec<Outcome> {
+        let values = serde_json::to_value(model).unwrap_or_default();
+        model
+            .entity_ids()
+            .into_iter()
+            .filter(|id| id.kind() == self.applies_to)
+            .filter_map(|id| {
+                let value = &values[id.kind()][id.name()];
+                if let Some(when) = &self.when {
+                    if !when.check(model, &id, value).is_empty() {
+                        return None;
+                    }
+                }
+                let failures = self.require.check(model, &id, value);
+                Some(Outcome {
+                    entity: id,
+                    failures,
+                })
+            })
+            .collect()
+    }
+
+    // findings for the outcomes that failed, for callers that already have them
+    pub fn findings(&self, model: &Model, outcomes: Vec<Outcome>) -> Vec<Finding> {
+        let what = self.description.as_deref().unwrap_or(&self.name);
+        outcomes
+            .into_iter()
+            .filter(|o| !o.passed())
+            .map(|o| Finding {
+                rule: self.name.clone(),
+                severity: self.severity.unwrap_or(Severity::Error),
+                message: format!("{what}: {}", o.failures.join(", ")),
+                location: model.sources.locate(&o.entity),
+                entities: vec![o.entity],
+            })
+            .collect()
+    }
+}
+
+impl Rule for PolicyRule {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn evaluate(&self, model: &Model) -> Vec<Finding> {
+        self.findings(model, self.outcomes(model))
+    }
+}
+
+impl Condition {
+    // the parts of the condition the entity does not meet
+    fn check(&self, model: &Model, id: &EntityRef, value: &Value) -> Vec<String> {
+        let mut failures = Vec::new();
+        for field in self.has.iter().flatten() {
+            if !is_set(&value[field]) {
+                failures.push(format!("`{field}` is not set"));
+            }
+        }
+        for field in self.lacks.iter().flatten() {
+            if is_set(&value[field]) {
+                failures.push(format!("`{field}` is set"));
+            }
+        }
+        for (field, expected) in self.equals.iter().flatten() {
+            let expected = serde_json::to_value(expected).unwrap_or_default();
+            if value[field] != expected {
+                failures.push(format!("`{field}` is not {expected}"));
+            }
+        }
+        for related in self.related.iter().flatten() {
+            let reached = related.follow(model, id);
+            let found = match &related.to {
+                Some(to) => reached.contains(to.as_str()),
+                None => !reached.is_empty(),
+            };
+            if !found {
+                let path: Vec<String> = related.path.iter().map(ToString::to_string).collect();
+                let to = related
+                    .to
+                    .as_ref()
+                    .map_or_else(|| "anything".to_string(), |to| format!("`{to}`"));
+                failures.push(format!("no `{}` path to {to}", path.join(" ")));
+            }
+        }
+        failures
+    }
+}
+
+impl Related {
+    // the entities at the end of the path from `id`
+    fn follow<'a>(&self, model: &'a Model, id: &'a str) -> HashSet<&'a str> {
+        let graph = model.graph();
+        let mut at: HashSet<&str> = HashSet::from([id]);
+        for step in &self.path {
+            let mut next = HashSet::new();
+            for e in at {
+                let found = match (step.reverse, step.repeat) {
+                    (false, false) => graph.neighbors(e, step.relation),
+                    (true, false) => graph.reverse_neighbors(e, step.relation),
+                    (false, true) => graph.reachable(e, &[step.relation]),
+                    (true, true) => graph.reverse_reachable(e, &[step.relation]),
+                };
+                if step.repeat {
+                    next.insert(e);
+                }
+                next.extend(found.into_iter().map(EntityRef::as_str));
+            }
+            at = next;
+        }
+        at.remove(id);
+        at
+    }
+}
+
+fn is_set(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy_rules() {
+        let model: Model = toml::from_str(
+            r#"
+[regulation.hipaa-phi]
+[data.record]
+contains = ["data.diagnosis"]
+[data.diagnosis]
+regulations = ["regulation.hipaa-phi"]
+[encryption.aes]
+[actor.ops]
+
+[store.db]
+data = ["data.record"]
+encryption = "encryption.aes"
+backup = "backup.nightly"
+[store.cache]
+data = ["data.record"]
+[store.logs]
+
+[server.web]
+owner = "actor.ops"
+stores = ["store.db"]
+[server.worker]
+"#,
+        )
+        .unwrap();
+        let mut policy: Policy = toml::from_str(
+            r#"
+[[rule]]
+name = "phi-stores-protected"
+applies_to = "store"
+when.related = [{ path = ["stores-data", "contains*", "regulated-by"], to = "regulation.hipaa-phi" }]
+require.has = ["backup", "encryption"]
+
+[[rule]]
+name = "server-owner"
+severity = "warning"
+applies_to = "server"
+require = { has = ["owner"] }
+
+[[rule]]
+name = "stores-hosted"
+applies_to = "store"
+require.related = [{ path = ["~hosts"] }]
+"#,
+        )
+        .unwrap();
+
+        let phi = policy.rule[0].outcomes(&model);
+        let passed: Vec<(&str, bool)> = phi
+            .iter()
+            .map(|o| (o.entity.as_str(), o.passed()))
+            .collect();
+        assert_eq!(vec![("store.db", true), ("store.cache", false)], passed);
+        assert_eq!(
+            vec!["`backup` is not set", "`encryption` is not set"],
+            phi[1].failures
+        );
+
+        let findings = crate::evaluate_rules(&model, &[Box::new(policy.rule.remove(1))]);
+        assert_eq!(1, findings.len());
+        assert_eq!(Severity::Warning, findings[0].severity);
+        assert_eq!(findings[0].entities, vec!["server.worker"]);
+
+        let hosted = policy.rule[1].outcomes(&model);
+        assert!(hosted[0].passed());
+        assert_eq!(vec!["no `~hosts` path to anything"], hosted[1].failures);
+    }
+
+    #[test]
+    fn bad_steps() {
+        assert!(Step::try_from("~stores-data*".to_string()).is_ok());
+        assert!(Step::try_from("owns".to_string()).is_err());
+    }
+}
@@ -0,0 +1,535 @@
+use crate::{
+    EntityRef, FlowControl, FlowControlAction, LookupError, Model, NetworkControls, NetworkID,
+    Relation, ServerID,
+};
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::fmt;
+
+// A step along a route, on to a network.
+#[derive(Serialize, Debug, Clone)]
+pub struct Hop {
+    pub network: NetworkID,
+    // the host routing the traffic from the previous network, when it
+    // was not connected to this one directly
+    pub via: Option<EntityRef>,
+    // whether the network is downstream of the previous one
+    pub inbound: bool,
+    // the controllers of the rules which allowed the traffic across
+    pub controllers: Vec<ServerID>,
+}
+
+// How traffic from one host gets to another, starting on a network of the
+// first, and ending on a network of the second.
+#[derive(Serialize, Debug, Clone)]
+pub struct Route {
+    pub from: EntityRef,
+    pub to: EntityRef,
+    // the network of `from` the traffic starts on
+    pub start: NetworkID,
+    pub hops: Vec<Hop>,
+}
+
+impl Route {
+    // the controllers which allow the traffic along the route
+    #[must_use]
+    pub fn controllers(&self) -> Vec<&ServerID> {
+        let mut controllers: Vec<&ServerID> = Vec::new();
+        for c in self.hops.iter().flat_map(|h| &h.controllers) {
+            if !controllers.contains(&c) {
+                controllers.push(c);
+            }
+        }
+        controllers
+    }
+
+    // each pair of networks the traffic crosses between, and the host
+    // routing it, if any
+    pub fn crossings(&self) -> impl Iterator<Item = (&NetworkID, &Hop)> {
+        std::iter::once(&self.start)
+            .chain(self.hops.iter().map(|h| &h.network))
+            .zip(&self.hops)
+    }
+}
+
+impl fmt::Display for Route {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}", self.from, self.start)?;
+        for hop in &self.hops {
+            if let Some(via) = &hop.via {
+                write!(f, " -> {via}")?;
+            }
+            write!(f, " -> {}", hop.network)?;
+            if !hop.controllers.is_empty() {
+                let controllers: Vec<&str> =
+                    hop.controllers.iter().map(EntityRef::as_str).collect();
+                write!(f, " [{}]", controllers.join(", "))?;
+            }
+        }
+        write!(f, " -> {}", self.to)
+    }
+}
+
+// Which servers and endpoints can reach each other.
+#[derive(Serialize, Debug)]
+pub struct Reachability {
+    pub hosts: Vec<EntityRef>,
+    // `routes[i][j]` is the route from `hosts[i]` to `hosts[j]`, if any
+    pub routes: Vec<Vec<Option<Route>>>,
+}
+
+// Find a route from a server or endpoint to another.  Traffic moves
+// between networks through hosts with interfaces on both, through the
+// interfaces networks have on each other, and between a network and those
+// it is hosted on, unless it is a VPN.  Crossing from one network to
+// another, the traffic must be let out by the egress controls of the one,
+// and in by the ingress controls of the other.
+#[allow(clippy::missing_errors_doc)]
+pub fn reach(model: &Model, from: &str, to: &str) -> Result<Option<Route>, LookupError> {
+    for id in [from, to] {
+        let host = id.split_once('.').is_some_and(|(kind, _)| {
+            matches!(kind, "server" | "endpoint") && model.entity_by_id(id).is_ok()
+        });
+        if !host {
+            return Err(LookupError::NotFound(id.to_string()));
+        }
+    }
+    Ok(route(model, &Traffic::between(from, to)))
+}
+
+// What is travelling between networks, for the network controls to
+// decide on.
+pub(crate) struct Traffic<'a> {
+    pub from: &'a str,
+    // without a destination, traffic is let through when some traffic
+    // from its source could be
+    pub to: Option<&'a str>,
+    // a rule naming the flows it is for applies to those flows only
+    pub flow: Option<&'a str>,
+    // whether network controls are applied at all
+    pub enforce: bool,
+}
+
+impl<'a> Traffic<'a> {
+    pub fn between(from: &'a str, to: &'a str) -> Traffic<'a> {
+        Traffic {
+            from,
+            to: Some(to),
+            flow: None,
+            enforce: true,
+        }
+    }
+}
+
+// the shortest route for traffic from one host to another
+pub(crate) fn route(model: &Model, traffic: &Traffic) -> Option<Route> {
+    let graph = model.graph();
+    let to = traffic.to?;
+    let start = graph
+        .neighbors(traffic.from, Relation::ConnectsTo)
+        .into_iter()
+        .map(EntityRef::as_str);
+    let found = search(model, traffic, start);
+    let last = graph.neighbors(to, Relation::ConnectsTo);
+    let end = found
+        .keys()
+        .position(|n| last.iter().any(|e| e.as_str() == *n))?;
+
+    let mut hops = Vec::new();
+    let mut at = end;
+    while let (network, Some(previous)) = found
+        .get_index(at)
+        .map(|(n, step)| (n, step.previous))
+        .unwrap()
+    {
+        let step = &found[at];
+        hops.push(Hop {
+            network: network.parse().ok()?,
+            via: step.via.and_then(|v| v.parse().ok()),
+            inbound: step.inbound,
+            controllers: step.controllers.clone(),
+        });
+        at = previous;
+    }
+    hops.reverse();
+    Some(Route {
+        from: traffic.from.parse().ok()?,
+        to: to.parse().ok()?,
+        start: found.get_index(at)?.0.parse().ok()?,
+        hops,
+    })
+}
+
+// the servers and endpoints an entity is, or runs on
+pub(crate) fn hosts_of<'a>(model: &'a Model, id: &'a str) -> Vec<&'a str> {
+    let graph = model.graph();
+    if id.starts_with("server.") || id.starts_with("endpoint.") {
+        return vec![id];
+    }
+    graph.hosts(id).into_iter().map(EntityRef::as_str).collect()
+}
+
+// Whether each server and endpoint can reach every other.
+pub fn reachability(model: &Model) -> Reachability {
+    let hosts: Vec<EntityRef> = model
+        .entity_ids()
+        .into_iter()
+        .filter(|id| matches!(id.kind(), "server" | "endpoint"))
+        .collect();
+    let routes = hosts
+        .iter()
+        .map(|from| {
+            hosts
+                .iter()
+                .map(|to| {
+                    if from == to {
+                        None
+                    } else {
+                        reach(model, from, to).ok().flatten()
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    Reachability { hosts, routes }
+}
+
+// The networks traffic from an entity can get to from those it starts
+// on.  Without a destination, a network is reached when some traffic
+// could be let on to it.
+pub(crate) fn reachable_networks<'a>(
+    model: &'a Model,
+    from: &'a str,
+    start: impl Iterator<Item = &'a str>,
+) -> Vec<&'a str> {
+    let traffic = Traffic {
+        from,
+        to: None,
+        flow: None,
+        enforce: true,
+    };
+    search(model, &traffic, start).into_keys().collect()
+}
+
+struct Step<'a> {
+    // the index of the network this one was reached from
+    previous: Option<usize>,
+    via: Option<&'a str>,
+    inbound: bool,
+    controllers: Vec<ServerID>,
+}
+
+// breadth first, so each network is reached by a shortest route
+fn search<'a>(
+    model: &'a Model,
+    traffic: &Traffic,
+    start: impl Iterator<Item = &'a str>,
+) -> IndexMap<&'a str, Step<'a>> {
+    let mut found: IndexMap<&str, Step> = start
+        .map(|n| {
+            let step = Step {
+                previous: None,
+                via: None,
+                inbound: false,
+                controllers: Vec::new(),
+            };
+            (n, step)
+        })
+        .collect();
+    let mut next = 0;
+    while let Some((&network, step)) = found.get_index(next) {
+        let routed_by = step.via;
+        for (other, via, inbound) in adjacent(model, network) {
+            // a host routes between its own networks directly, not
+            // through another of them
+            if found.contains_key(other) || (via.is_some() && via == routed_by) {
+                continue;
+            }
+            if let Some(controllers) = crossing(model, traffic, network, other, via) {
+                let step = Step {
+                    previous: Some(next),
+                    via,
+                    inbound,
+                    controllers,
+                };
+                found.insert(other, step);
+            }
+        }
+        next += 1;
+    }
+    found
+}
+
+// the networks next to a network, the host between them if any, and
+// whether the next network is downstream
+fn adjacent<'a>(model: &'a Model, network: &str) -> Vec<(&'a str, Option<&'a str>, bool)> {
+    let graph = model.graph();
+    let mut adjacent = Vec::new();
+    let vpn = |n: &str| model.network_by_id(n).and_then(|n| n.vpn) == Some(true);
+
+    for member in graph.reverse_neighbors(network, Relation::ConnectsTo) {
+        match member.kind() {
+            "network" => {
+                let inbound = !downstream(model, member, network);
+                adjacent.push((member.as_str(), None, inbound));
+            }
+            "server" | "endpoint" => {
+                for other in graph.neighbors(member, Relation::ConnectsTo) {
+                    if other.as_str() != network {
+                        let inbound = downstream(model, member, other);
+                        adjacent.push((other.as_str(), Some(member.as_str()), inbound));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    for other in graph.neighbors(network, Relation::ConnectsTo) {
+        adjacent.push((other.as_str(), None, downstream(model, network, other)));
+    }
+    for other in graph.neighbors(network, Relation::HostedOn) {
+        if !vpn(network) {
+            adjacent.push((other.as_str(), None, false));
+        }
+    }
+    for other in graph.reverse_neighbors(network, Relation::HostedOn) {
+        if !vpn(other) {
+            adjacent.push((other.as_str(), None, true));
+        }
+    }
+    adjacent
+}
+
+// whether a host or network has a downstream interface on a network
+fn downstream(model: &Model, id: &str, network: &str) -> bool {
+    let interfaces = if let Some(server) = model.server_by_id(id) {
+        server.interfaces.as_ref()
+    } else if let Some(endpoint) = model.endpoint_by_id(id) {
+        endpoint.interfaces.as_ref()
+    } else {
+        model.network_by_id(id).and_then(|n| n.interfaces.as_ref())
+    };
+    interfaces.into_iter().flatten().any(|i| {
+        i.downstream == Some(true) && i.network.as_ref().is_some_and(|n| n.as_str() == network)
+    })
+}
+
+// The controllers letting traffic out of one network and in to the next,
+// or nothing when either denies it.
+pub(crate) fn crossing(
+    model: &Model,
+    traffic: &Traffic,
+    out_of: &str,
+    into: &str,
+    via: Option<&str>,
+) -> Option<Vec<ServerID>> {
+    if !traffic.enforce {
+        return Some(Vec::new());
+    }
+    let controls = |n: &str| model.network_by_id(n).and_then(|n| n.controls.as_ref());
+    let mut controllers = Vec::new();
+    for (controls, egress) in [(controls(out_of), true), (controls(into), false)] {
+        let Some(controls) = controls else {
+            continue;
+        };
+        match decide(model, controls, egress, traffic, via) {
+            Verdict::Deny => return None,
+            Verdict::Allow(rule) => {
+                if let Some(controller) = rule.and_then(|r| r.controller.as_ref()) {
+                    controllers.push(controller.clone());
+                }
+            }
+        }
+    }
+    Some(controllers)
+}
+
+enum Verdict<'a> {
+    // with the rule allowing the traffic, if not allowed by default
+    Allow(Option<&'a FlowControl>),
+    Deny,
+}
+
+// The first rule of the direction which applies to the traffic decides
+// whether it is let through, and otherwise the default for the direction,
+// which is to allow it.
+fn decide<'a>(
+    model: &Model,
+    controls: &'a NetworkControls,
+    egress: bool,
+    traffic: &Traffic,
+    via: Option<&str>,
+) -> Verdict<'a> {
+    let (rules, default) = if egress {
+        (controls.egress.as_ref(), controls.egress_default)
+    } else {
+        (controls.ingress.as_ref(), controls.ingress_default)
+    };
+    for rule in rules.into_iter().flatten() {
+        if let (Some(controller), Some(via)) = (&rule.controller, via) {
+            if controller.as_str() != via {
+                continue;
+            }
+        }
+        if rule
+            .sources
+            .as_ref()
+            .is_some_and(|s| !covers(model, s, traffic.from))
+        {
+            continue;
+        }
+        if let (Some(flows), Some(flow)) = (&rule.flows, traffic.flow) {
+            if !flows.iter().any(|f| f.as_str() == flow) {
+                continue;
+            }
+        }
+        let allow = rule.action.unwrap_or(FlowControlAction::Allow) == FlowControlAction::Allow;
+        match (&rule.destinations, traffic.to) {
+            (Some(destinations), Some(to)) if !covers(model, destinations, to) => continue,
+            // without a destination, a rule for some destinations lets
+            // some traffic through, but does not stop all of it
+            (Some(_), None) if !allow => continue,
+            _ => {}
+        }
+        return if allow {
+            Verdict::Allow(Some(rule))
+        } else {
+            Verdict::Deny
+        };
+    }
+    match default.unwrap_or(FlowControlAction::Allow) {
+        FlowControlAction::Allow => Verdict::Allow(None),
+        FlowControlAction::Deny => Verdict::Deny,
+    }
+}
+
+// whether a list of entities in a rule includes an entity: by naming it,
+// something it hosts, or a network it is on
+fn covers(model: &Model, ids: &[EntityRef], entity: &str) -> bool {
+    let graph = model.graph();
+    let networks = graph.neighbors(entity, Relation::ConnectsTo);
+    let hosted = graph.hosted(entity);
+    ids.iter()
+        .any(|id| id.as_str() == entity || networks.contains(&id) || hosted.contains(&id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes() {
+        let model: Model = toml::from_str(
+            r#"
+[network.internet]
+[network.dmz]
+controls.ingress_default = "deny"
+controls.ingress = [
+    { destinations = ["application.site"], controller = "server.fw" },
+]
+[network.office]
+controls.ingress_default = "deny"
+controls.egress = [{ destinations = ["network.internet"], action = "deny" }]
+[network.vpn]
+vpn = true
+host_networks = ["network.internet"]
+
+[server.fw]
+interfaces = [
+    { network = "network.internet" },
+    { network = "network.dmz", downstream = true },
+    { network = "network.office", downstream = true },
+]
+[server.web]
+interfaces = [{ network = "network.dmz" }]
+applications = ["application.site"]
+[server.db]
+interfaces = [{ network = "network.dmz" }]
+[endpoint.laptop]
+interfaces = [{ network = "network.internet" }]
+[endpoint.desk]
+interfaces = [{ network = "network.office" }]
+[endpoint.remote]
+interfaces = [{ network = "network.vpn" }]
+[application.site]
+"#,
+        )
+        .unwrap();
+
+        let route = reach(&model, "endpoint.laptop", "server.web")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            "endpoint.laptop -> network.internet -> server.fw -> network.dmz [server.fw] -> server.web",
+            route.to_string()
+        );
+        assert!(route.hops[0].inbound);
+        assert_eq!(route.controllers(), vec!["server.fw"]);
+
+        // only the site is let in to the dmz
+        assert!(reach(&model, "endpoint.laptop", "server.db")
+            .unwrap()
+            .is_none());
+        // nothing is let in to the office
+        assert!(reach(&model, "server.web", "endpoint.desk")
+            .unwrap()
+            .is_none());
+        // the office is not let out to the internet, but may reach the dmz
+        assert!(reach(&model, "endpoint.desk", "endpoint.laptop")
+            .unwrap()
+            .is_none());
+        assert!(reach(&model, "endpoint.desk", "server.web")
+            .unwrap()
+            .is_some());
+        // a VPN is carried over the internet, but is not part of it
+        assert!(reach(&model, "endpoint.laptop", "endpoint.remote")
+            .unwrap()
+            .is_none());
+
+        assert!(matches!(
+            reach(&model, "endpoint.laptop", "application.site"),
+            Err(LookupError::NotFound(_))
+        ));
+
+        let matrix = reachability(&model);
+        assert_eq!(6, matrix.hosts.len());
+        assert!(matrix.routes[0][0].is_none());
+    }
+
+    #[test]
+    fn deployed_destination() {
+        let model: Model = toml::from_str(
+            r#"
+[network.internet]
+[network.dmz]
+controls.ingress_default = "deny"
+controls.ingress = [{ destinations = ["application.api"] }]
+
+[server.gw]
+interfaces = [
+    { network = "network.internet" },
+    { network = "network.dmz", downstream = true },
+]
+[server.api]
+interfaces = [{ network = "network.dmz" }]
+[server.spare]
+interfaces = [{ network = "network.dmz" }]
+[endpoint.laptop]
+interfaces = [{ network = "network.internet" }]
+[application.api]
+[deployment.api]
+targets = ["server.api"]
+applications = ["application.api"]
+"#,
+        )
+        .unwrap();
+        // the rule names the application, which only a deployment puts on
+        // the server
+        assert!(reach(&model, "endpoint.laptop", "server.api")
+            .unwrap()
+            .is_some());
+        assert!(reach(&model, "endpoint.laptop", "server.spare")
+            .unwrap()
+            .is_none());
+    }
+}
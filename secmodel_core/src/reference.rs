@@ -0,0 +1,310 @@
+use crate::{
+    Acceptance, Actor, Agent, Application, Authentication, Authorization, Backup, Boundary,
+    Channel, Classification, Control, Data, Deployment, Encryption, Endpoint, EntityError,
+    EntityID, Flow, Key, Model, Network, Process, Protocol, Regulation, Risk, Secret, Server,
+    Store, Threat,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::str::FromStr;
+
+// The kind of entity a reference may point at, named by the type prefix of
+// its ID, e.g. `risk` in `risk.no-backup`.
+pub trait EntityKind {
+    // `None` when a reference may point at any kind of entity
+    const KIND: Option<&'static str>;
+}
+
+// An entity kind that is stored in a table of the model.
+pub trait Lookup: EntityKind + Sized {
+    fn table(model: &Model) -> Option<&IndexMap<String, Self>>;
+}
+
+// The kind of references that may point at any entity, such as flow
+// sources and destinations.
+#[derive(Debug)]
+pub struct AnyEntity;
+
+impl EntityKind for AnyEntity {
+    const KIND: Option<&'static str> = None;
+}
+
+macro_rules! entity_kinds {
+    ($($entity:ident => $kind:ident),* $(,)?) => {
+        $(
+            impl EntityKind for $entity {
+                const KIND: Option<&'static str> = Some(stringify!($kind));
+            }
+
+            impl Lookup for $entity {
+                fn table(model: &Model) -> Option<&IndexMap<String, Self>> {
+                    model.$kind.as_ref()
+                }
+            }
+        )*
+    };
+}
+
+entity_kinds! {
+    Acceptance => acceptance,
+    Actor => actor,
+    Agent => agent,
+    Application => application,
+    Authentication => authentication,
+    Authorization => authorization,
+    Backup => backup,
+    Boundary => boundary,
+    Channel => channel,
+    Classification => classification,
+    Control => control,
+    Data => data,
+    Deployment => deployment,
+    Encryption => encryption,
+    Endpoint => endpoint,
+    Flow => flow,
+    Key => key,
+    Network => network,
+    Process => process,
+    Protocol => protocol,
+    Regulation => regulation,
+    Risk => risk,
+    Secret => secret,
+    Server => server,
+    Store => store,
+    Threat => threat,
+}
+
+// A reference to an entity by its ID, e.g. `store.main-mysql`.  References
+// are checked when they are parsed: the ID must have a known type prefix
+// and a name, and when `K` is a concrete entity type, the prefix must
+// match it.  `EntityRef` on its own accepts a reference to any entity.
+pub struct EntityRef<K = AnyEntity> {
+    id: String,
+    dot: usize,
+    kind: PhantomData<fn() -> K>,
+}
+
+impl<K: EntityKind> EntityRef<K> {
+    #[allow(clippy::missing_errors_doc)]
+    pub fn parse(id: &str) -> Result<EntityRef<K>, EntityError> {
+        let Some(dot) = id.find('.').filter(|&dot| dot + 1 < id.len()) else {
+            return Err(EntityError::MalformedID(id.to_string()));
+        };
+        EntityID::from_str(id)?;
+        if let Some(kind) = K::KIND {
+            if &id[..dot] != kind {
+                return Err(EntityError::UnexpectedEntityType {
+                    id: id.to_string(),
+                    expected: kind,
+                });
+            }
+        }
+        Ok(EntityRef {
+            id: id.to_string(),
+            dot,
+            kind: PhantomData,
+        })
+    }
+}
+
+// the type prefix of an ID which may not parse, e.g. `store`, or the whole
+// ID when it has none
+pub(crate) fn kind_of(id: &str) -> &str {
+    id.split_once('.').map_or(id, |(kind, _)| kind)
+}
+
+impl<K> EntityRef<K> {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.id
+    }
+
+    // the type prefix of the ID, e.g. `store`
+    #[must_use]
+    pub fn kind(&self) -> &str {
+        &self.id[..self.dot]
+    }
+
+    // the ID without its type prefix, e.g. `main-mysql`
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.id[self.dot + 1..]
+    }
+
+    // the ID was checked when the reference was parsed, so cannot fail
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)]
+    pub fn entity_id(&self) -> EntityID {
+        EntityID::from_str(&self.id).unwrap()
+    }
+
+    // the same reference, without the constraint on its kind
+    #[must_use]
+    pub fn untyped(&self) -> EntityRef {
+        EntityRef {
+            id: self.id.clone(),
+            dot: self.dot,
+            kind: PhantomData,
+        }
+    }
+}
+
+impl<K> EntityRef<K> {
+    // the same reference, constrained to the kind `L`, if it is one
+    #[must_use]
+    pub fn downcast<L: EntityKind>(&self) -> Option<EntityRef<L>> {
+        match L::KIND {
+            Some(kind) if kind != self.kind() => None,
+            _ => Some(EntityRef {
+                id: self.id.clone(),
+                dot: self.dot,
+                kind: PhantomData,
+            }),
+        }
+    }
+}
+
+impl<K: Lookup> EntityRef<K> {
+    // a reference to the entity with the given name in the table for `K`
+    #[must_use]
+    pub fn named(name: &str) -> EntityRef<K> {
+        let kind = K::KIND.unwrap_or_default();
+        EntityRef {
+            id: format!("{kind}.{name}"),
+            dot: kind.len(),
+            kind: PhantomData,
+        }
+    }
+
+    #[must_use]
+    pub fn resolve<'a>(&self, model: &'a Model) -> Option<&'a K> {
+        K::table(model)?.get(self.name())
+    }
+}
+
+impl<K: EntityKind> FromStr for EntityRef<K> {
+    type Err = EntityError;
+
+    fn from_str(id: &str) -> Result<EntityRef<K>, EntityError> {
+        EntityRef::parse(id)
+    }
+}
+
+impl<K> Clone for EntityRef<K> {
+    fn clone(&self) -> Self {
+        EntityRef {
+            id: self.id.clone(),
+            dot: self.dot,
+            kind: PhantomData,
+        }
+    }
+}
+
+impl<K> Deref for EntityRef<K> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.id
+    }
+}
+
+impl<K> AsRef<str> for EntityRef<K> {
+    fn as_ref(&self) -> &str {
+        &self.id
+    }
+}
+
+impl<K> fmt::Display for EntityRef<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.id)
+    }
+}
+
+impl<K> fmt::Debug for EntityRef<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EntityRef({:?})", self.id)
+    }
+}
+
+impl<K, L> PartialEq<EntityRef<L>> for EntityRef<K> {
+    fn eq(&self, other: &EntityRef<L>) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<K> Eq for EntityRef<K> {}
+
+impl<K> PartialEq<str> for EntityRef<K> {
+    fn eq(&self, other: &str) -> bool {
+        self.id == other
+    }
+}
+
+impl<K> PartialEq<&str> for EntityRef<K> {
+    fn eq(&self, other: &&str) -> bool {
+        self.id == *other
+    }
+}
+
+impl<K> PartialEq<String> for EntityRef<K> {
+    fn eq(&self, other: &String) -> bool {
+        &self.id == other
+    }
+}
+
+impl<K> Hash for EntityRef<K> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<K> Serialize for EntityRef<K> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.id)
+    }
+}
+
+impl<'de, K: EntityKind> Deserialize<'de> for EntityRef<K> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        EntityRef::parse(&id).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncryptionID, Model};
+
+    #[test]
+    fn parse_references() {
+        let r: EncryptionID = "encryption.tls".parse().unwrap();
+        assert_eq!(("encryption", "tls"), (r.kind(), r.name()));
+        assert_eq!(r, "encryption.tls");
+        assert!(matches!(
+            "risk.foo".parse::<EncryptionID>(),
+            Err(EntityError::UnexpectedEntityType { .. })
+        ));
+        assert!(matches!(
+            "encryption".parse::<EncryptionID>(),
+            Err(EntityError::MalformedID(_))
+        ));
+        let any: EntityRef = "risk.foo".parse().unwrap();
+        assert_eq!("risk", any.kind());
+        assert!("widget.foo".parse::<EntityRef>().is_err());
+    }
+
+    #[test]
+    fn rejected_when_deserialized() {
+        let e = toml::from_str::<Model>("[store.db]\nencryption = \"risk.foo\"\n")
+            .err()
+            .unwrap();
+        assert!(e.message().contains("expected `encryption`"));
+        assert_eq!(Some(24..34), e.span());
+        assert!(toml::from_str::<Model>("[server.a]\nowner = \"cable\"\n").is_err());
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{AcceptanceID, ControlID, EntityRef, Model, Relation, RiskID};
+use serde::Serialize;
+use std::fmt;
+
+// How a risk an entity is exposed to has been dealt with, from most to
+// least complete.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum Treatment {
+    // a control on the entity removes the risk
+    Remediated,
+    // a control on the entity reduces the risk
+    Mitigated,
+    // the risk has been knowingly accepted for the entity
+    Accepted,
+    Unmitigated,
+}
+
+impl fmt::Display for Treatment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Treatment::Remediated => "Remediated",
+            Treatment::Mitigated => "Mitigated",
+            Treatment::Accepted => "Accepted",
+            Treatment::Unmitigated => "Unmitigated",
+        };
+        write!(f, "{name}")
+    }
+}
+
+// A risk an entity is exposed to, the controls on the entity which
+// address it, and any acceptances of the risk for the entity.
+#[derive(Serialize, Debug, Clone)]
+pub struct RegisterEntry {
+    pub entity: EntityRef,
+    pub risk: RiskID,
+    pub treatment: Treatment,
+    pub controls: Vec<ControlID>,
+    pub acceptances: Vec<AcceptanceID>,
+}
+
+// Every risk of every entity in the model, in model order.
+pub fn risk_register(model: &Model) -> Vec<RegisterEntry> {
+    model
+        .entity_ids()
+        .iter()
+        .flat_map(|id| entity_risks(model, id))
+        .collect()
+}
+
+// The risks listed on an entity, each treated by the controls the entity
+// lists which remediate or mitigate it, or failing that, accepted by an
+// acceptance which applies to the entity and has not expired.
+pub fn entity_risks(model: &Model, id: &str) -> Vec<RegisterEntry> {
+    let Ok(entity) = id.parse::<EntityRef>() else {
+        return Vec::new();
+    };
+    let graph = model.graph();
+    let controls = graph.neighbors(id, Relation::ControlledBy);
+    graph
+        .neighbors(id, Relation::ExposesRisk)
+        .into_iter()
+        .filter_map(|risk| risk.parse::<RiskID>().ok())
+        .map(|risk| {
+            let addressing = |relation| -> Vec<ControlID> {
+                controls
+                    .iter()
+                    .filter(|c| {
+                        graph
+                            .neighbors(c, relation)
+                            .iter()
+                            .any(|r| r.as_str() == risk.as_str())
+                    })
+                    .filter_map(|c| c.parse().ok())
+                    .collect()
+            };
+            let remediating = addressing(Relation::Remediates);
+            let mitigating = addressing(Relation::Mitigates);
+            let acceptances: Vec<AcceptanceID> = graph
+                .reverse_neighbors(&risk, Relation::Accepts)
+                .into_iter()
+                .filter(|a| {
+                    graph
+                        .neighbors(a, Relation::AppliesTo)
+                        .iter()
+                        .any(|e| e.as_str() == id)
+                })
+                .filter_map(|a| a.parse::<AcceptanceID>().ok())
+                .filter(|a| a.resolve(model).is_some_and(|a| !a.expired()))
+                .collect();
+            let treatment = if !remediating.is_empty() {
+                Treatment::Remediated
+            } else if !mitigating.is_empty() {
+                Treatment::Mitigated
+            } else if !acceptances.is_empty() {
+                Treatment::Accepted
+            } else {
+                Treatment::Unmitigated
+            };
+            let mut controls = remediating;
+            for control in mitigating {
+                if !controls.contains(&control) {
+                    controls.push(control);
+                }
+            }
+            RegisterEntry {
+                entity: entity.clone(),
+                risk,
+                treatment,
+                controls,
+                acceptances,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn treatments() {
+        let model: Model = toml::from_str(
+            r#"
+[risk.injection]
+[risk.leak]
+[risk.loss]
+
+[control.waf]
+mitigates = ["risk.injection"]
+[control.prepared-statements]
+remediates = ["risk.injection"]
+[control.dlp]
+mitigates = ["risk.leak"]
+
+[application.web]
+controls = ["control.waf", "control.prepared-statements"]
+risks = ["risk.injection", "risk.leak"]
+[store.db]
+controls = ["control.dlp"]
+risks = ["risk.leak", "risk.loss"]
+[store.backup]
+risks = ["risk.loss"]
+
+[acceptance.loss]
+risk = "risk.loss"
+entities = ["store.db"]
+[acceptance.lapsed]
+risk = "risk.loss"
+entities = ["store.backup"]
+expires = 2020-01-01
+"#,
+        )
+        .unwrap();
+        let register = risk_register(&model);
+        let summary: Vec<(&str, &str, Treatment)> = register
+            .iter()
+            .map(|e| (e.entity.as_str(), e.risk.as_str(), e.treatment))
+            .collect();
+        assert_eq!(
+            vec![
+                ("application.web", "risk.injection", Treatment::Remediated),
+                // the controls of other entities do not count
+                ("application.web", "risk.leak", Treatment::Unmitigated),
+                ("store.db", "risk.leak", Treatment::Mitigated),
+                ("store.db", "risk.loss", Treatment::Accepted),
+                ("store.backup", "risk.loss", Treatment::Unmitigated),
+            ],
+            summary
+        );
+        assert_eq!(
+            register[0].controls,
+            vec!["control.prepared-statements", "control.waf"]
+        );
+        assert_eq!(register[3].acceptances, vec!["acceptance.loss"]);
+    }
+}
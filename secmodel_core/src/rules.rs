@@ -0,0 +1,211 @@
+use crate::{
+    BackupOnSameServer, DataID, DetachedFlowController, Diagnostic, EntityRef, FlowID,
+    FlowNotAllowed, Ipv6UncontrolledChannel, Ipv6WithoutIngressControls, Ipv6WithoutRanges,
+    KeyWithData, Location, Model, Relation, Severity, SingleFactorAuthentication,
+    UncontrolledAccess, UnusedFlowControl, UnverifiedOffsiteBackup, WeakEncryption,
+};
+use serde::Serialize;
+
+// A weakness in the modelled system found by a rule, as opposed to a
+// mistake in the model itself, which `validate` reports.
+#[derive(Serialize, Debug, Clone)]
+pub struct Finding {
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+    // the entities involved, the first being where the weakness lies
+    pub entities: Vec<EntityRef>,
+    pub location: Option<Location>,
+}
+
+impl Finding {
+    // the finding as a diagnostic, so it can be reported alongside them
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic {
+            severity: self.severity,
+            entity: self
+                .entities
+                .first()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            field: String::new(),
+            message: format!("{} [{}]", self.message, self.rule),
+            location: self.location.clone(),
+        }
+    }
+}
+
+pub trait Rule {
+    fn name(&self) -> &str;
+    fn evaluate(&self, model: &Model) -> Vec<Finding>;
+}
+
+#[must_use]
+pub fn builtin_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(UnencryptedSensitiveFlow),
+        Box::new(FlowNotAllowed),
+        Box::new(UnusedFlowControl),
+        Box::new(DetachedFlowController),
+        Box::new(Ipv6WithoutRanges),
+        Box::new(Ipv6WithoutIngressControls),
+        Box::new(Ipv6UncontrolledChannel),
+        Box::new(UncontrolledAccess),
+        Box::new(WeakEncryption),
+        Box::new(SingleFactorAuthentication),
+        Box::new(KeyWithData),
+        Box::new(UnverifiedOffsiteBackup),
+        Box::new(BackupOnSameServer),
+    ]
+}
+
+// Run the built in rules over a model.
+pub fn evaluate(model: &Model) -> Vec<Finding> {
+    evaluate_rules(model, &builtin_rules())
+}
+
+pub fn evaluate_rules(model: &Model, rules: &[Box<dyn Rule>]) -> Vec<Finding> {
+    rules.iter().flat_map(|rule| rule.evaluate(model)).collect()
+}
+
+// Flows carrying classified or regulated data over a channel which is not
+// encrypted, and is not carried over an encrypted channel either.  Data
+// with a classification of level 0 is taken to be public.
+pub struct UnencryptedSensitiveFlow;
+
+impl Rule for UnencryptedSensitiveFlow {
+    fn name(&self) -> &'static str {
+        "unencrypted-sensitive-flow"
+    }
+
+    fn evaluate(&self, model: &Model) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for (name, flow) in model.flow.iter().flatten() {
+            let id = FlowID::named(name);
+            let Some(channel) = &flow.channel else {
+                continue;
+            };
+            // encrypted itself, or carried over a channel which is
+            if model
+                .channel_stack(channel)
+                .iter()
+                .any(|c| c.encryption.is_some())
+            {
+                continue;
+            }
+            let sensitive: Vec<&DataID> = flow
+                .data
+                .iter()
+                .flatten()
+                .filter(|d| classified(model, d) || regulated(model, d))
+                .collect();
+            if sensitive.is_empty() {
+                continue;
+            }
+
+            let severity = if sensitive.iter().any(|d| regulated(model, d)) {
+                Severity::Error
+            } else {
+                Severity::Warning
+            };
+            let data = sensitive
+                .iter()
+                .map(|d| format!("`{d}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let mut entities = vec![id.untyped(), channel.untyped()];
+            entities.extend(sensitive.iter().map(|d| d.untyped()));
+            findings.push(Finding {
+                rule: self.name().to_string(),
+                severity,
+                message: format!("{data} sent over unencrypted channel `{channel}`"),
+                entities,
+                location: model.sources.locate(&format!("{id}.channel")),
+            });
+        }
+        findings
+    }
+}
+
+fn classified(model: &Model, data: &DataID) -> bool {
+    model
+        .effective_classification(data)
+        .is_some_and(|c| c.resolve(model).and_then(|c| c.level) != Some(0))
+}
+
+// whether the data, or any data it contains, is subject to a regulation
+pub(crate) fn regulated(model: &Model, data: &DataID) -> bool {
+    let graph = model.graph();
+    std::iter::once(data.as_str())
+        .chain(
+            graph
+                .reachable(data, &[Relation::Contains])
+                .into_iter()
+                .map(EntityRef::as_str),
+        )
+        .any(|d| !graph.neighbors(d, Relation::RegulatedBy).is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unencrypted_flows() {
+        let model: Model = toml::from_str(
+            r#"
+[classification.public]
+level = 0
+[classification.internal]
+level = 1
+[regulation.pii]
+
+[data.brochure]
+classification = "classification.public"
+[data.notes]
+classification = "classification.internal"
+[data.record]
+contains = ["data.ssn"]
+[data.ssn]
+regulations = ["regulation.pii"]
+
+[encryption.ssh]
+[channel.ipv4]
+[channel.plain]
+over = ["channel.ipv4"]
+[channel.ssh]
+encryption = "encryption.ssh"
+over = ["channel.ipv4"]
+[channel.tunnelled]
+over = ["channel.ssh"]
+
+[flow.download]
+channel = "channel.plain"
+data = ["data.brochure"]
+[flow.sync]
+channel = "channel.plain"
+data = ["data.notes", "data.brochure"]
+[flow.upload]
+channel = "channel.plain"
+data = ["data.record"]
+[flow.admin]
+channel = "channel.tunnelled"
+data = ["data.record"]
+"#,
+        )
+        .unwrap();
+        let findings = evaluate(&model);
+        assert_eq!(2, findings.len());
+
+        assert_eq!(Severity::Warning, findings[0].severity);
+        assert_eq!(
+            findings[0].entities,
+            vec!["flow.sync", "channel.plain", "data.notes"]
+        );
+
+        // data containing regulated data is regulated too
+        assert_eq!(Severity::Error, findings[1].severity);
+        assert_eq!(findings[1].entities[0], "flow.upload");
+        assert_eq!("flow.upload", findings[1].diagnostic().entity);
+    }
+}
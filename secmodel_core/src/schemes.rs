@@ -0,0 +1,225 @@
+use crate::access::{channel_schemes, untrusted_networks};
+use crate::rules::{Finding, Rule};
+use crate::surface::locations;
+use crate::{Authentication, EncryptionID, FlowID, Model, Relation, Severity};
+use std::collections::HashSet;
+
+// Protocol versions with known weaknesses, normalised as `weak_version`
+// compares them.
+const WEAK_VERSIONS: [&str; 7] = [
+    "ssl2", "ssl2.0", "ssl3", "ssl3.0", "tls1", "tls1.0", "tls1.1",
+];
+
+// Parts of algorithm names which mark them as broken or too weak.
+const WEAK_ALGORITHMS: [&str; 7] = ["rc4", "des", "3des", "md5", "sha1", "null", "export"];
+
+// Algorithms whose keys must be longer, being asymmetric.
+const ASYMMETRIC_ALGORITHMS: [&str; 3] = ["rsa", "dsa", "dh"];
+
+// Encryption schemes allowing deprecated protocol versions, broken
+// algorithms or short keys.
+pub struct WeakEncryption;
+
+impl Rule for WeakEncryption {
+    fn name(&self) -> &'static str {
+        "weak-encryption"
+    }
+
+    fn evaluate(&self, model: &Model) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for (name, encryption) in model.encryption.iter().flatten() {
+            let id = EncryptionID::named(name);
+            let mut finding = |field: &str, n: Option<usize>, message: String| {
+                let key = match n {
+                    Some(n) => format!("{id}.{field}[{n}]"),
+                    None => format!("{id}.{field}"),
+                };
+                findings.push(Finding {
+                    rule: self.name().to_string(),
+                    severity: Severity::Warning,
+                    message,
+                    entities: vec![id.untyped()],
+                    location: model.sources.locate(&key),
+                });
+            };
+            let versions = encryption.protocol_versions.iter().flatten();
+            for (n, version) in versions.enumerate() {
+                if weak_version(version) {
+                    finding(
+                        "protocol_versions",
+                        Some(n),
+                        format!("`{id}` allows {version}"),
+                    );
+                }
+            }
+            let algorithms = encryption.algorithms.iter().flatten();
+            for (n, algorithm) in algorithms.enumerate() {
+                if tokens(algorithm).any(|t| WEAK_ALGORITHMS.contains(&t.as_str())) {
+                    finding("algorithms", Some(n), format!("`{id}` uses {algorithm}"));
+                }
+            }
+            if let Some(length) = encryption.key_length {
+                let asymmetric = encryption
+                    .algorithms
+                    .iter()
+                    .flatten()
+                    .flat_map(|a| tokens(a).collect::<Vec<_>>())
+                    .any(|t| ASYMMETRIC_ALGORITHMS.contains(&t.as_str()));
+                let minimum = if asymmetric { 2048 } else { 128 };
+                if length < minimum {
+                    finding(
+                        "key_length",
+                        None,
+                        format!("`{id}` uses {length} bit keys, fewer than {minimum}"),
+                    );
+                }
+            }
+        }
+        findings
+    }
+}
+
+// Flows from an untrusted network over a channel whose authentication is
+// known to need only a single factor.
+pub struct SingleFactorAuthentication;
+
+impl Rule for SingleFactorAuthentication {
+    fn name(&self) -> &'static str {
+        "single-factor-authentication"
+    }
+
+    fn evaluate(&self, model: &Model) -> Vec<Finding> {
+        let untrusted = untrusted_networks(model);
+        let graph = model.graph();
+        let mut findings = Vec::new();
+        for (name, flow) in model.flow.iter().flatten() {
+            let id = FlowID::named(name);
+            let Some(channel) = &flow.channel else {
+                continue;
+            };
+            let (Some(authentication), _) = channel_schemes(model, channel) else {
+                continue;
+            };
+            let single = authentication
+                .resolve(model)
+                .and_then(Authentication::multi_factor)
+                == Some(false);
+            if !single {
+                continue;
+            }
+            let from: Vec<&str> = graph
+                .reverse_neighbors(&id, Relation::FlowsTo)
+                .into_iter()
+                .flat_map(|s| locations(model, s, &mut HashSet::new()))
+                .filter(|n| untrusted.iter().any(|u| u.as_str() == *n))
+                .collect();
+            let Some(network) = from.first() else {
+                continue;
+            };
+            findings.push(Finding {
+                rule: self.name().to_string(),
+                severity: Severity::Warning,
+                message: format!(
+                    "`{id}` comes from untrusted `{network}` with single factor `{authentication}`"
+                ),
+                entities: vec![id.untyped(), authentication.untyped()],
+                location: model.sources.locate(&format!("{id}.channel")),
+            });
+        }
+        findings
+    }
+}
+
+// e.g. "TLS 1.0", "TLSv1" and "tls1.0" are all "tls1.0" or "tls1"
+fn weak_version(version: &str) -> bool {
+    let normal: String = version
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let normal = normal.replacen("sslv", "ssl", 1).replacen("tlsv", "tls", 1);
+    WEAK_VERSIONS.contains(&normal.as_str())
+}
+
+// the lower case, alphanumeric parts of an algorithm name, e.g. "des",
+// "cbc3" and "sha" for "DES-CBC3-SHA"
+fn tokens(algorithm: &str) -> impl Iterator<Item = String> + '_ {
+    algorithm
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluate_rules;
+
+    #[test]
+    fn schemes() {
+        let model: Model = toml::from_str(
+            r#"
+[network.internet]
+[boundary.outside]
+trust = 0
+networks = ["network.internet"]
+[endpoint.browser]
+interfaces = [{ network = "network.internet" }]
+[application.site]
+
+[encryption.tls]
+algorithms = ["AES-256-GCM", "RC4-SHA"]
+protocol_versions = ["TLS 1.2", "TLSv1.0"]
+[encryption.rsa]
+algorithms = ["RSA"]
+key_length = 1024
+[encryption.aes]
+algorithms = ["AES"]
+key_length = 256
+
+[authentication.password]
+factors = ["password"]
+[authentication.totp]
+factors = ["password", "otp"]
+[authorization.rbac]
+model = "rbac"
+
+[channel.https]
+encryption = "encryption.tls"
+authentication = "authentication.password"
+[channel.admin]
+authentication = "authentication.totp"
+
+[flow.login]
+sources = ["endpoint.browser"]
+destinations = ["application.site"]
+channel = "channel.https"
+[flow.admin]
+sources = ["endpoint.browser"]
+destinations = ["application.site"]
+channel = "channel.admin"
+"#,
+        )
+        .unwrap();
+        let messages = |rule: Box<dyn Rule>| -> Vec<String> {
+            evaluate_rules(&model, &[rule])
+                .into_iter()
+                .map(|f| f.message)
+                .collect()
+        };
+        assert_eq!(
+            messages(Box::new(WeakEncryption)),
+            vec![
+                "`encryption.tls` allows TLSv1.0",
+                "`encryption.tls` uses RC4-SHA",
+                "`encryption.rsa` uses 1024 bit keys, fewer than 2048",
+            ]
+        );
+        assert_eq!(
+            messages(Box::new(SingleFactorAuthentication)),
+            vec!["`flow.login` comes from untrusted `network.internet` with single factor `authentication.password`"]
+        );
+        let totp = model.authentication_by_id("authentication.totp").unwrap();
+        assert_eq!(Some(true), totp.multi_factor());
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{ControlID, Model, Rating, Relation, RiskID, RiskScoring};
+use serde::Serialize;
+
+// How much of a risk remains once a control is applied.  A mitigating
+// control halves the likelihood of the risk being realised, while a
+// remediating control removes most of it.
+pub const MITIGATED: f64 = 0.5;
+pub const REMEDIATED: f64 = 0.1;
+
+// The scores of a risk, from 1 to 25, as its likelihood times its impact.
+#[derive(Serialize, Debug, Clone)]
+pub struct RiskScore {
+    pub risk: RiskID,
+    pub likelihood: Rating,
+    pub impact: Rating,
+    // before any controls
+    pub inherent: f64,
+    // after the controls which mitigate or remediate the risk
+    pub residual: f64,
+    // the expected loss per year, for risks scored with FAIR
+    pub annual_loss: Option<f64>,
+    pub controls: Vec<ControlID>,
+    // whether the risk has been accepted, by an acceptance which has not
+    // expired, and so is not expected to be reduced further
+    pub accepted: bool,
+}
+
+// Score every risk rated in the model, highest residual score first.
+// Those without a likelihood, impact or scoring are left out.
+pub fn score_risks(model: &Model) -> Vec<RiskScore> {
+    let mut scores: Vec<RiskScore> = model
+        .risk
+        .iter()
+        .flatten()
+        .filter_map(|(name, _)| score_risk(model, &RiskID::named(name)))
+        .collect();
+    scores.sort_by(|a, b| b.residual.total_cmp(&a.residual));
+    scores
+}
+
+pub fn score_risk(model: &Model, id: &RiskID) -> Option<RiskScore> {
+    let risk = id.resolve(model)?;
+    let (likelihood, impact, annual_loss) = match risk.scoring {
+        None if risk.likelihood.is_none() && risk.impact.is_none() => return None,
+        None => (None, None, None),
+        Some(RiskScoring::Cvss { score }) => (None, Some(cvss_rating(score)), None),
+        Some(RiskScoring::Fair {
+            frequency,
+            magnitude,
+        }) => (
+            Some(frequency_rating(frequency)),
+            None,
+            Some(frequency * magnitude),
+        ),
+    };
+    // ratings given explicitly take precedence over those derived from
+    // the scoring model
+    let likelihood = risk.likelihood.or(likelihood).unwrap_or(Rating::Medium);
+    let impact = risk.impact.or(impact).unwrap_or(Rating::Medium);
+    let inherent = f64::from(likelihood.value()) * f64::from(impact.value());
+
+    let graph = model.graph();
+    let mut residual = inherent;
+    let mut controls = Vec::new();
+    for (relation, factor) in [
+        (Relation::Mitigates, MITIGATED),
+        (Relation::Remediates, REMEDIATED),
+    ] {
+        for control in graph.reverse_neighbors(id, relation) {
+            if let Ok(control) = control.parse::<ControlID>() {
+                residual *= factor;
+                controls.push(control);
+            }
+        }
+    }
+
+    let accepted = graph
+        .reverse_neighbors(id, Relation::Accepts)
+        .into_iter()
+        .filter_map(|a| model.acceptance_by_id(a))
+        .any(|a| !a.expired());
+
+    Some(RiskScore {
+        risk: id.clone(),
+        likelihood,
+        impact,
+        inherent,
+        residual,
+        annual_loss,
+        controls,
+        accepted,
+    })
+}
+
+// the qualitative severity bands of CVSS v3
+fn cvss_rating(score: f64) -> Rating {
+    match score {
+        s if s < 0.1 => Rating::VeryLow,
+        s if s < 4.0 => Rating::Low,
+        s if s < 7.0 => Rating::Medium,
+        s if s < 9.0 => Rating::High,
+        _ => Rating::VeryHigh,
+    }
+}
+
+// loss events per year
+fn frequency_rating(frequency: f64) -> Rating {
+    match frequency {
+        f if f >= 10.0 => Rating::VeryHigh,
+        f if f >= 1.0 => Rating::High,
+        f if f >= 0.1 => Rating::Medium,
+        f if f >= 0.01 => Rating::Low,
+        _ => Rating::VeryLow,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn residual_risk() {
+        let model: Model = toml::from_str(
+            r#"
+[risk.phishing]
+likelihood = "high"
+impact = "high"
+[risk.rce]
+scoring = { model = "cvss", score = 9.8 }
+likelihood = "low"
+[risk.fraud]
+scoring = { model = "fair", frequency = 2, magnitude = 5000 }
+[risk.unrated]
+
+[control.mfa]
+mitigates = ["risk.phishing"]
+[control.training]
+mitigates = ["risk.phishing"]
+[control.patching]
+remediates = ["risk.rce"]
+
+[acceptance.fraud]
+risk = "risk.fraud"
+expires = "2999-12-31"
+"#,
+        )
+        .unwrap();
+        let scores = score_risks(&model);
+        let summary: Vec<(&str, f64, f64)> = scores
+            .iter()
+            .map(|s| (s.risk.as_str(), s.inherent, s.residual))
+            .collect();
+        assert_eq!(
+            vec![
+                ("risk.fraud", 12.0, 12.0),
+                ("risk.phishing", 16.0, 4.0),
+                ("risk.rce", 10.0, 1.0),
+            ],
+            summary
+        );
+        assert_eq!(Rating::High, scores[0].likelihood);
+        assert_eq!(Some(10000.0), scores[0].annual_loss);
+        assert_eq!(Rating::VeryHigh, scores[2].impact);
+        assert_eq!(scores[1].controls, vec!["control.mfa", "control.training"]);
+        assert!(scores[0].accepted);
+        assert!(!scores[1].accepted);
+    }
+}
@@ -0,0 +1,187 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+use toml_edit::{ImDocument, Item, Table, Value};
+
+// A position in a model source file.  Line and column are 1-based, and
+// the text of the line is kept so a diagnostic can be rendered on its own.
+#[derive(Serialize, Debug, Clone)]
+pub struct Location {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub width: usize,
+    pub text: String,
+}
+
+impl Location {
+    #[must_use]
+    pub fn from_span(file: &str, src: &str, span: &Range<usize>) -> Location {
+        let start = span.start.min(src.len());
+        let line_start = src[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = src[start..].find('\n').map_or(src.len(), |i| start + i);
+        let text = &src[line_start..line_end];
+        let end = span.end.clamp(start, line_end);
+        Location {
+            file: file.to_string(),
+            line: src[..start].matches('\n').count() + 1,
+            column: src[line_start..start].chars().count() + 1,
+            width: src[start..end].chars().count().max(1),
+            text: text.trim_end_matches('\r').to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+struct SourceFile {
+    path: String,
+    text: String,
+}
+
+// Records where each table and key of a model was declared, keyed by its
+// dotted path, e.g. `store.main-mysql` or `store.main-mysql.backing`.
+// Elements of arrays of tables are keyed by index, e.g. `comment[0]`.
+#[derive(Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+    spans: HashMap<String, (usize, Range<usize>)>,
+}
+
+impl SourceMap {
+    pub fn add(&mut self, path: &str, text: String) {
+        let file = self.files.len();
+        if let Ok(doc) = ImDocument::parse(text.as_str()) {
+            self.index_table(file, "", doc.as_table());
+        }
+        self.files.push(SourceFile {
+            path: path.to_string(),
+            text,
+        });
+    }
+
+    #[must_use]
+    pub fn locate(&self, key: &str) -> Option<Location> {
+        let (file, span) = self.spans.get(key)?;
+        let source = &self.files[*file];
+        Some(Location::from_span(&source.path, &source.text, span))
+    }
+
+    // the file in which the table or key was declared
+    #[must_use]
+    pub fn file_of(&self, key: &str) -> Option<&str> {
+        let (file, _) = self.spans.get(key)?;
+        Some(&self.files[*file].path)
+    }
+
+    #[must_use]
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    // take over the files and spans of another map, passing each of its
+    // keys through `rekey`; keys already present here are kept
+    pub fn extend(&mut self, other: SourceMap, rekey: impl Fn(&str) -> String) {
+        let offset = self.files.len();
+        self.files.extend(other.files);
+        for (key, (file, span)) in other.spans {
+            self.insert(file + offset, rekey(&key), Some(span));
+        }
+    }
+
+    fn insert(&mut self, file: usize, path: String, span: Option<Range<usize>>) {
+        if let Some(span) = span {
+            self.spans.entry(path).or_insert((file, span));
+        }
+    }
+
+    fn index_table(&mut self, file: usize, prefix: &str, table: &Table) {
+        for (key, item) in table {
+            let path = join(prefix, key);
+            let span = match item {
+                Item::Table(t) => t
+                    .span()
+                    .or_else(|| table.key(key).and_then(toml_edit::Key::span)),
+                _ => item
+                    .span()
+                    .or_else(|| table.key(key).and_then(toml_edit::Key::span)),
+            };
+            self.insert(file, path.clone(), span);
+            self.index_item(file, &path, item);
+        }
+    }
+
+    fn index_item(&mut self, file: usize, path: &str, item: &Item) {
+        match item {
+            Item::Table(t) => self.index_table(file, path, t),
+            Item::ArrayOfTables(a) => {
+                for (n, t) in a.iter().enumerate() {
+                    let path = format!("{path}[{n}]");
+                    self.insert(file, path.clone(), t.span());
+                    self.index_table(file, &path, t);
+                }
+            }
+            Item::Value(v) => self.index_value(file, path, v),
+            Item::None => (),
+        }
+    }
+
+    fn index_value(&mut self, file: usize, path: &str, value: &Value) {
+        match value {
+            Value::InlineTable(t) => {
+                for (key, v) in t {
+                    let path = join(path, key);
+                    let span = v
+                        .span()
+                        .or_else(|| t.key(key).and_then(toml_edit::Key::span));
+                    self.insert(file, path.clone(), span);
+                    self.index_value(file, &path, v);
+                }
+            }
+            Value::Array(a) => {
+                for (n, v) in a.iter().enumerate() {
+                    let path = format!("{path}[{n}]");
+                    self.insert(file, path.clone(), v.span());
+                    self.index_value(file, &path, v);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+fn join(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locate_keys() {
+        let src = "[network.prod]\ntitle = \"Production\"\ncontrols.ingress = [{flows=[\"flow.a\"]}]\n\n[[comment]]\nentity = \"network.prod\"\n";
+        let mut sources = SourceMap::default();
+        sources.add("model.toml", src.to_string());
+
+        let l = sources.locate("network.prod").unwrap();
+        assert_eq!((1, 1), (l.line, l.column));
+        let l = sources.locate("network.prod.title").unwrap();
+        assert_eq!((2, 9, 12), (l.line, l.column, l.width));
+        let l = sources
+            .locate("network.prod.controls.ingress[0].flows")
+            .unwrap();
+        assert_eq!((3, 28), (l.line, l.column));
+        let l = sources.locate("comment[0].entity").unwrap();
+        assert_eq!("model.toml:6:10", l.to_string());
+    }
+}
@@ -0,0 +1,194 @@
+use crate::reach::reachable_networks;
+use crate::reference::kind_of;
+use crate::{EntityRef, LookupError, Model, NetworkID, Relation, ThreatID};
+use indexmap::IndexSet;
+use serde::Serialize;
+use std::collections::HashSet;
+
+// What a threat can attack: the entities exposed to its risks which can
+// be reached from where the threat acts.
+#[derive(Serialize, Debug)]
+pub struct AttackSurface {
+    pub threat: ThreatID,
+    // the networks the threat acts from
+    pub positions: Vec<NetworkID>,
+    // the networks reachable from those positions
+    pub networks: Vec<NetworkID>,
+    // exposed entities on, or hosted on, a reachable network
+    pub exposed: Vec<EntityRef>,
+    // exposed entities which the threat cannot reach, or whose place in
+    // the network is unknown
+    pub unreachable: Vec<EntityRef>,
+}
+
+// The attack surface of every threat in the model.
+pub fn attack_surfaces(model: &Model) -> Vec<AttackSurface> {
+    model
+        .threat
+        .iter()
+        .flatten()
+        .filter_map(|(name, _)| attack_surface(model, &format!("threat.{name}")).ok())
+        .collect()
+}
+
+// A threat acts from the networks it names, and those of its actors: the
+// networks of the endpoints and servers they own, or which host the agents
+// acting for them.  The threat reaches those networks its traffic could be
+// let on to, as `reach` finds.
+#[allow(clippy::missing_errors_doc)]
+pub fn attack_surface(model: &Model, threat_id: &str) -> Result<AttackSurface, LookupError> {
+    let threat = model
+        .threat_by_id(threat_id)
+        .ok_or_else(|| LookupError::NotFound(threat_id.to_string()))?;
+    let graph = model.graph();
+
+    let mut positions: IndexSet<&str> = graph
+        .neighbors(threat_id, Relation::ConnectsTo)
+        .into_iter()
+        .map(EntityRef::as_str)
+        .collect();
+    for actor in threat.actor.iter().flatten() {
+        let agents = graph.reverse_neighbors(actor, Relation::ActsFor);
+        let owned = graph.reverse_neighbors(actor, Relation::OwnedBy);
+        let hosts = agents
+            .into_iter()
+            .flat_map(|a| graph.reverse_neighbors(a, Relation::Hosts))
+            .chain(owned);
+        for host in hosts {
+            positions.extend(
+                graph
+                    .neighbors(host, Relation::ConnectsTo)
+                    .into_iter()
+                    .map(EntityRef::as_str),
+            );
+        }
+    }
+
+    let networks = reachable_networks(model, threat_id, positions.iter().copied());
+
+    let mut exposed: IndexSet<&str> = IndexSet::new();
+    let mut unreachable: IndexSet<&str> = IndexSet::new();
+    for risk in threat.risk.iter().flatten() {
+        for entity in graph.reverse_neighbors(risk, Relation::ExposesRisk) {
+            // a threat is not its own attack surface
+            if entity.kind() == "threat" {
+                continue;
+            }
+            let located = locations(model, entity, &mut HashSet::new());
+            if located.iter().any(|n| networks.contains(n)) {
+                exposed.insert(entity.as_str());
+            } else {
+                unreachable.insert(entity.as_str());
+            }
+        }
+    }
+
+    Ok(AttackSurface {
+        threat: ThreatID::named(threat_id.strip_prefix("threat.").unwrap_or(threat_id)),
+        positions: positions.iter().filter_map(|n| n.parse().ok()).collect(),
+        networks: networks.iter().filter_map(|n| n.parse().ok()).collect(),
+        exposed: exposed.iter().filter_map(|e| e.parse().ok()).collect(),
+        unreachable: unreachable.iter().filter_map(|e| e.parse().ok()).collect(),
+    })
+}
+
+// the networks an entity is on: those of the hosts it runs on, the stores
+// or flows holding data, and the ends of a flow
+pub(crate) fn locations<'a>(
+    model: &'a Model,
+    id: &'a str,
+    seen: &mut HashSet<&'a str>,
+) -> IndexSet<&'a str> {
+    if !seen.insert(id) {
+        return IndexSet::new();
+    }
+    let graph = model.graph();
+    let related: Vec<&EntityRef> = match kind_of(id) {
+        "network" => return IndexSet::from([id]),
+        "server" | "endpoint" => {
+            return graph
+                .neighbors(id, Relation::ConnectsTo)
+                .into_iter()
+                .map(EntityRef::as_str)
+                .collect()
+        }
+        "application" | "store" | "agent" => graph.hosts(id),
+        "data" => graph
+            .reverse_neighbors(id, Relation::StoresData)
+            .into_iter()
+            .chain(graph.reverse_neighbors(id, Relation::CarriesData))
+            .collect(),
+        "flow" => graph
+            .reverse_neighbors(id, Relation::FlowsTo)
+            .into_iter()
+            .chain(graph.neighbors(id, Relation::FlowsTo))
+            .collect(),
+        "channel" => graph.reverse_neighbors(id, Relation::Uses),
+        _ => Vec::new(),
+    };
+    related
+        .into_iter()
+        .flat_map(|r| locations(model, r, seen))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn surface() {
+        let model: Model = toml::from_str(
+            r#"
+[risk.rce]
+[risk.phishing]
+[actor.insider]
+
+[network.internet]
+[network.dmz]
+[network.office]
+[network.lab]
+
+[server.fw]
+interfaces = [{ network = "network.internet" }, { network = "network.dmz" }]
+[server.web]
+interfaces = [{ network = "network.dmz" }]
+applications = ["application.site"]
+[server.analysis]
+interfaces = [{ network = "network.lab" }]
+risks = ["risk.rce"]
+[endpoint.desk]
+interfaces = [{ network = "network.office" }]
+owner = "actor.insider"
+risks = ["risk.phishing"]
+
+[application.site]
+risks = ["risk.rce"]
+[process.shell]
+risks = ["risk.rce"]
+
+[threat.criminals]
+networks = ["network.internet"]
+risk = ["risk.rce", "risk.phishing"]
+[threat.insider]
+actor = ["actor.insider"]
+risk = ["risk.phishing"]
+"#,
+        )
+        .unwrap();
+        let s = attack_surface(&model, "threat.criminals").unwrap();
+        assert_eq!(s.networks, vec!["network.internet", "network.dmz"]);
+        assert_eq!(s.exposed, vec!["application.site"]);
+        assert_eq!(
+            s.unreachable,
+            vec!["server.analysis", "process.shell", "endpoint.desk"]
+        );
+
+        // an insider acts from the networks of what they own
+        let s = attack_surface(&model, "threat.insider").unwrap();
+        assert_eq!(s.positions, vec!["network.office"]);
+        assert_eq!(s.exposed, vec!["endpoint.desk"]);
+
+        assert_eq!(2, attack_surfaces(&model).len());
+    }
+}
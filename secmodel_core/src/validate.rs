@@ -0,0 +1,853 @@
+use crate::{
+    Custody, DataID, EntityRef, IpNet, Location, Model, Network, NetworkControls, NetworkID,
+    NetworkInterface, Relation,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fmt::Write;
+use std::net::IpAddr;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+// A problem found in a model, attributed to the entity and field where
+// the offending value was declared, and where known, its source location.
+#[derive(Serialize, Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub entity: String,
+    pub field: String,
+    pub message: String,
+    pub location: Option<Location>,
+}
+
+impl Diagnostic {
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    // the entity and field the diagnostic is about, e.g. `store.db.backing`
+    #[must_use]
+    pub fn subject(&self) -> String {
+        match (self.entity.is_empty(), self.field.is_empty()) {
+            (true, _) => String::new(),
+            (false, true) => self.entity.clone(),
+            (false, false) => format!("{}.{}", self.entity, self.field),
+        }
+    }
+
+    // render the diagnostic compiler style, with the offending source line
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut text = format!("{}: {}\n", self.severity, self.message);
+        let mut gutter = String::new();
+        if let Some(l) = &self.location {
+            gutter = " ".repeat(l.line.to_string().len());
+            let _ = writeln!(text, "{gutter}--> {l}");
+            let _ = writeln!(text, "{gutter} |");
+            let _ = writeln!(text, "{} | {}", l.line, l.text);
+            let _ = writeln!(
+                text,
+                "{gutter} | {}{}",
+                " ".repeat(l.column - 1),
+                "^".repeat(l.width)
+            );
+        }
+        let subject = self.subject();
+        if !subject.is_empty() {
+            let _ = writeln!(text, "{gutter} = in {subject}");
+        }
+        text
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "{location}: ")?;
+        }
+        write!(f, "{}: ", self.severity)?;
+        let subject = self.subject();
+        if !subject.is_empty() {
+            write!(f, "{subject}: ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+struct Validator<'a> {
+    model: &'a Model,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Validator<'_> {
+    // `key` is the path of the offending value relative to the entity, which
+    // may be more precise than `field`, e.g. `regulations[1]`
+    fn error(&mut self, entity: &str, field: &str, key: &str, message: String) {
+        self.report(Severity::Error, entity, field, key, message);
+    }
+
+    fn warning(&mut self, entity: &str, field: &str, message: String) {
+        self.report(Severity::Warning, entity, field, field, message);
+    }
+
+    fn report(
+        &mut self,
+        severity: Severity,
+        entity: &str,
+        field: &str,
+        key: &str,
+        message: String,
+    ) {
+        let sources = &self.model.sources;
+        let location = sources
+            .locate(&format!("{entity}.{key}"))
+            .or_else(|| sources.locate(&format!("{entity}.{field}")))
+            .or_else(|| sources.locate(entity));
+        self.diagnostics.push(Diagnostic {
+            severity,
+            entity: entity.to_string(),
+            field: field.to_string(),
+            message,
+            location,
+        });
+    }
+
+    // check that a reference names an existing entity; its kind was
+    // checked when the model was parsed
+    fn reference<K>(&mut self, entity: &str, field: &str, id: &EntityRef<K>) {
+        self.reference_at(entity, field, field, id);
+    }
+
+    fn reference_at<K>(&mut self, entity: &str, field: &str, key: &str, id: &EntityRef<K>) {
+        if self.model.entity_by_id(id).is_err() {
+            self.error(
+                entity,
+                field,
+                key,
+                format!("reference to unknown entity `{id}`"),
+            );
+        }
+    }
+
+    fn optional<K>(&mut self, entity: &str, field: &str, id: Option<&EntityRef<K>>) {
+        if let Some(id) = id {
+            self.reference(entity, field, id);
+        }
+    }
+
+    fn list<K>(&mut self, entity: &str, field: &str, ids: Option<&Vec<EntityRef<K>>>) {
+        self.list_at(entity, field, field, ids);
+    }
+
+    fn list_at<K>(
+        &mut self,
+        entity: &str,
+        field: &str,
+        key: &str,
+        ids: Option<&Vec<EntityRef<K>>>,
+    ) {
+        for (n, id) in ids.into_iter().flatten().enumerate() {
+            self.reference_at(entity, field, &format!("{key}[{n}]"), id);
+        }
+    }
+
+    fn interfaces(&mut self, entity: &str, interfaces: Option<&Vec<NetworkInterface>>) {
+        for (n, i) in interfaces.into_iter().flatten().enumerate() {
+            self.optional(entity, "interfaces", i.network.as_ref());
+            let (Some(network), Some(address)) = (&i.network, &i.address) else {
+                continue;
+            };
+            // only a network with ranges of the address's family can be
+            // said not to contain it
+            let Some(ranges) = network.resolve(self.model).map(Network::ranges) else {
+                continue;
+            };
+            let family: Vec<&IpNet> = ranges
+                .iter()
+                .filter(|r| r.addr().is_ipv4() == address.is_ipv4())
+                .collect();
+            if !family.is_empty() && !family.iter().any(|r| r.contains(address)) {
+                self.error(
+                    entity,
+                    "interfaces",
+                    &format!("interfaces[{n}].address"),
+                    format!("address {address} is not in the ranges of `{network}`"),
+                );
+            }
+        }
+    }
+
+    // addresses given to more than one interface
+    fn duplicate_addresses(&mut self) {
+        let model = self.model;
+        let hosts = model
+            .network
+            .iter()
+            .flatten()
+            .map(|(name, e)| (format!("network.{name}"), &e.interfaces))
+            .chain(
+                model
+                    .server
+                    .iter()
+                    .flatten()
+                    .map(|(name, e)| (format!("server.{name}"), &e.interfaces)),
+            )
+            .chain(
+                model
+                    .endpoint
+                    .iter()
+                    .flatten()
+                    .map(|(name, e)| (format!("endpoint.{name}"), &e.interfaces)),
+            );
+        // an address only clashes with one on the same network, or on a
+        // network whose ranges overlap it, so private ranges may be reused
+        let mut seen: Vec<(IpAddr, Option<NetworkID>, String)> = Vec::new();
+        for (entity, interfaces) in hosts {
+            for (n, i) in interfaces.iter().flatten().enumerate() {
+                let Some(address) = i.address else {
+                    continue;
+                };
+                let network = i
+                    .network
+                    .clone()
+                    .or_else(|| model.network_containing(&address));
+                let first = seen.iter().find(|(a, other, _)| {
+                    *a == address && same_network(model, other.as_ref(), network.as_ref())
+                });
+                if let Some((_, _, first)) = first {
+                    let message = format!("address {address} is also used by {first}");
+                    let key = format!("interfaces[{n}].address");
+                    self.error(&entity, "interfaces", &key, message);
+                } else {
+                    seen.push((address, network, format!("interfaces[{n}] of `{entity}`")));
+                }
+            }
+        }
+    }
+
+    // servers listing applications and stores which deployments put
+    // elsewhere, and deployments putting them on servers which do not list
+    // them; those no server lists are left to deployments
+    fn deployments(&mut self) {
+        let model = self.model;
+        let graph = model.graph();
+        let deployed = model
+            .application
+            .iter()
+            .flatten()
+            .map(|(name, _)| (format!("application.{name}"), "applications"))
+            .chain(
+                model
+                    .store
+                    .iter()
+                    .flatten()
+                    .map(|(name, _)| (format!("store.{name}"), "stores")),
+            );
+        for (id, field) in deployed {
+            let deployments: Vec<&EntityRef> = graph
+                .reverse_neighbors(&id, Relation::Deploys)
+                .into_iter()
+                .filter(|d| d.kind() == "deployment")
+                .collect();
+            let listing: Vec<&EntityRef> = graph
+                .reverse_neighbors(&id, Relation::Hosts)
+                .into_iter()
+                .filter(|h| h.kind() == "server")
+                .collect();
+            if deployments.is_empty() || listing.is_empty() {
+                continue;
+            }
+            let targeted = |server: &EntityRef| {
+                deployments
+                    .iter()
+                    .any(|d| graph.neighbors(d, Relation::Targets).contains(&server))
+            };
+            for server in listing.iter().filter(|s| !targeted(s)) {
+                let listed = model.server_by_id(server).and_then(|s| match field {
+                    "applications" => s.applications.iter().flatten().position(|a| a == &id),
+                    _ => s.stores.iter().flatten().position(|a| a == &id),
+                });
+                let n = listed.unwrap_or_default();
+                let message = format!("lists `{id}`, which no deployment puts on it");
+                let key = format!("{field}[{n}]");
+                self.report(Severity::Warning, server, field, &key, message);
+            }
+            for deployment in deployments {
+                for server in graph.neighbors(deployment, Relation::Targets) {
+                    if listing.contains(&server) {
+                        continue;
+                    }
+                    let message = format!("puts `{id}` on `{server}`, which does not list it");
+                    self.warning(deployment, "targets", message);
+                }
+            }
+        }
+    }
+
+    // entities listed by more than one boundary, which diagrams draw in the
+    // first of them only
+    fn overlapping_boundaries(&mut self) {
+        fn ids<K>(list: Option<&Vec<EntityRef<K>>>) -> Vec<&str> {
+            list.into_iter().flatten().map(EntityRef::as_str).collect()
+        }
+        let graph = self.model.graph();
+        for (name, e) in self.model.boundary.iter().flatten() {
+            let id = format!("boundary.{name}");
+            let fields = [
+                ("networks", ids(e.networks.as_ref())),
+                ("servers", ids(e.servers.as_ref())),
+                ("endpoints", ids(e.endpoints.as_ref())),
+                ("applications", ids(e.applications.as_ref())),
+            ];
+            for (field, listed) in fields {
+                for (n, entity) in listed.into_iter().enumerate() {
+                    let boundaries = graph.reverse_neighbors(entity, Relation::Encloses);
+                    let Some(first) = boundaries.first().filter(|b| b.as_str() != id) else {
+                        continue;
+                    };
+                    let message = format!("`{entity}` is also in `{first}`");
+                    let key = format!("{field}[{n}]");
+                    self.report(Severity::Warning, &id, field, &key, message);
+                }
+            }
+        }
+    }
+
+    // where a key or secret is held, and whether it is overdue for rotation
+    fn custody(&mut self, entity: &str, kind: &str, custody: &Custody) {
+        self.optional(entity, "store", custody.store.as_ref());
+        self.optional(entity, "server", custody.server.as_ref());
+        self.optional(entity, "owner", custody.owner.as_ref());
+        if let Some(due) = custody
+            .rotation_due()
+            .filter(|_| custody.rotation_overdue())
+        {
+            let message = format!("{kind} was due to be rotated on {due}");
+            self.warning(entity, "rotated", message);
+        }
+    }
+
+    // ranges of different networks which share addresses
+    fn overlapping_ranges(&mut self) {
+        let mut ranges: Vec<(String, IpNet)> = Vec::new();
+        for (name, e) in self.model.network.iter().flatten() {
+            let id = format!("network.{name}");
+            let (mut v4, mut v6) = (0, 0);
+            for range in e.ranges() {
+                let (field, n) = match range {
+                    IpNet::V4(_) => ("ipv4_ranges", &mut v4),
+                    IpNet::V6(_) => ("ipv6_ranges", &mut v6),
+                };
+                for (other, earlier) in &ranges {
+                    if *other != id && overlaps(earlier, &range) {
+                        self.report(
+                            Severity::Warning,
+                            &id,
+                            field,
+                            &format!("{field}[{n}]"),
+                            format!("range {range} overlaps {earlier} of `{other}`"),
+                        );
+                    }
+                }
+                *n += 1;
+                ranges.push((id.clone(), range));
+            }
+        }
+    }
+
+    fn network_controls(&mut self, entity: &str, controls: Option<&NetworkControls>) {
+        let Some(controls) = controls else {
+            return;
+        };
+        for (field, rules) in [
+            ("controls.ingress", &controls.ingress),
+            ("controls.egress", &controls.egress),
+        ] {
+            for (n, rule) in rules.iter().flatten().enumerate() {
+                let key = |k: &str| format!("{field}[{n}].{k}");
+                let mitigates = rule.mitigates.as_ref();
+                self.list_at(entity, field, &key("mitigates"), mitigates);
+                let remediates = rule.remediates.as_ref();
+                self.list_at(entity, field, &key("remediates"), remediates);
+                let flows = rule.flows.as_ref();
+                self.list_at(entity, field, &key("flows"), flows);
+                if let Some(controller) = &rule.controller {
+                    self.reference_at(entity, field, &key("controller"), controller);
+                }
+                let sources = rule.sources.as_ref();
+                self.list_at(entity, field, &key("sources"), sources);
+                let destinations = rule.destinations.as_ref();
+                self.list_at(entity, field, &key("destinations"), destinations);
+            }
+        }
+    }
+}
+
+fn overlaps(a: &IpNet, b: &IpNet) -> bool {
+    a.contains(b) || b.contains(a)
+}
+
+// whether interfaces on the two networks share an address space: the same
+// network, or networks with overlapping ranges
+fn same_network(model: &Model, a: Option<&NetworkID>, b: Option<&NetworkID>) -> bool {
+    if a == b {
+        return true;
+    }
+    let ranges = |id: Option<&NetworkID>| {
+        id.and_then(|id| id.resolve(model))
+            .map(Network::ranges)
+            .unwrap_or_default()
+    };
+    let theirs = ranges(b);
+    ranges(a)
+        .iter()
+        .any(|r| theirs.iter().any(|o| overlaps(r, o)))
+}
+
+// Check that every reference in the model resolves to an entity, and that
+// its parts agree: data is classified at least as highly as the data it
+// contains, acceptances are approved and unexpired, interface addresses
+// are within the ranges of their networks and not used twice on one,
+// ranges of different networks do not overlap, servers list what
+// deployments put on them, nothing is in more than one boundary, and keys
+// and secrets are rotated when due.
+#[allow(clippy::too_many_lines)]
+#[must_use]
+pub fn validate(model: &Model) -> Vec<Diagnostic> {
+    let mut v = Validator {
+        model,
+        diagnostics: Vec::new(),
+    };
+
+    for (name, e) in model.actor.iter().flatten() {
+        let id = &format!("actor.{name}");
+        v.list(id, "risks", e.risks.as_ref());
+    }
+
+    for (name, e) in model.agent.iter().flatten() {
+        let id = &format!("agent.{name}");
+        v.optional(id, "actor", e.actor.as_ref());
+        v.optional(id, "endpoint", e.endpoint.as_ref());
+        v.optional(id, "server", e.server.as_ref());
+        v.optional(id, "process", e.process.as_ref());
+        v.list(id, "risks", e.risks.as_ref());
+    }
+
+    for (name, e) in model.application.iter().flatten() {
+        let id = &format!("application.{name}");
+        v.list(id, "controls", e.controls.as_ref());
+        v.list(id, "risks", e.risks.as_ref());
+    }
+
+    for (name, e) in model.channel.iter().flatten() {
+        let id = &format!("channel.{name}");
+        v.list(id, "over", e.over.as_ref());
+        v.list(id, "protocols", e.protocols.as_ref());
+        v.optional(id, "encryption", e.encryption.as_ref());
+        v.optional(id, "authentication", e.authentication.as_ref());
+        v.optional(id, "authorization", e.authorization.as_ref());
+        v.list(id, "controls", e.controls.as_ref());
+        v.list(id, "risks", e.risks.as_ref());
+    }
+
+    for (name, e) in model.data.iter().flatten() {
+        let id = &format!("data.{name}");
+        v.list(id, "contains", e.contains.as_ref());
+        v.optional(id, "classification", e.classification.as_ref());
+        v.list(id, "regulations", e.regulations.as_ref());
+        v.list(id, "subjects", e.subjects.as_ref());
+        v.optional(id, "owner", e.owner.as_ref());
+        v.optional(id, "steward", e.steward.as_ref());
+        v.list(id, "risks", e.risks.as_ref());
+        v.list(id, "controls", e.controls.as_ref());
+
+        // data is at least as sensitive as anything it contains
+        if let (Some(declared), Some(contents)) = (
+            &e.classification,
+            model.contents_classification(&DataID::named(name)),
+        ) {
+            if model.classification_level(&contents) > model.classification_level(declared) {
+                v.warning(
+                    id,
+                    "classification",
+                    format!(
+                        "classified as `{declared}`, lower than the `{contents}` data it contains"
+                    ),
+                );
+            }
+        }
+    }
+
+    for (name, e) in model.flow.iter().flatten() {
+        let id = &format!("flow.{name}");
+        v.optional(id, "channel", e.channel.as_ref());
+        v.list(id, "sources", e.sources.as_ref());
+        v.list(id, "destinations", e.destinations.as_ref());
+        v.list(id, "data", e.data.as_ref());
+        v.list(id, "risks", e.risks.as_ref());
+    }
+
+    for (name, e) in model.store.iter().flatten() {
+        let id = &format!("store.{name}");
+        v.list(id, "data", e.data.as_ref());
+        v.optional(id, "backing", e.backing.as_ref());
+        v.optional(id, "backup", e.backup.as_ref());
+        v.optional(id, "encryption", e.encryption.as_ref());
+        v.optional(id, "authentication", e.authentication.as_ref());
+        v.optional(id, "authorization", e.authorization.as_ref());
+        v.list(id, "controls", e.controls.as_ref());
+        v.list(id, "risks", e.risks.as_ref());
+    }
+
+    for (name, e) in model.backup.iter().flatten() {
+        let id = &format!("backup.{name}");
+        v.optional(id, "target", e.target.as_ref());
+        v.optional(id, "encryption", e.encryption.as_ref());
+        v.list(id, "risks", e.risks.as_ref());
+    }
+
+    for (name, e) in model.network.iter().flatten() {
+        let id = &format!("network.{name}");
+        v.interfaces(id, e.interfaces.as_ref());
+        v.list(id, "protocols", e.protocols.as_ref());
+        v.network_controls(id, e.controls.as_ref());
+        v.list(id, "host_networks", e.host_networks.as_ref());
+        v.list(id, "risks", e.risks.as_ref());
+    }
+
+    for (name, e) in model.server.iter().flatten() {
+        let id = &format!("server.{name}");
+        v.interfaces(id, e.interfaces.as_ref());
+        v.list(id, "applications", e.applications.as_ref());
+        v.list(id, "stores", e.stores.as_ref());
+        v.optional(id, "owner", e.owner.as_ref());
+        v.list(id, "risks", e.risks.as_ref());
+    }
+
+    for (name, e) in model.endpoint.iter().flatten() {
+        let id = &format!("endpoint.{name}");
+        v.interfaces(id, e.interfaces.as_ref());
+        v.list(id, "applications", e.applications.as_ref());
+        v.list(id, "agents", e.agents.as_ref());
+        v.list(id, "stores", e.stores.as_ref());
+        v.optional(id, "owner", e.owner.as_ref());
+        v.list(id, "risks", e.risks.as_ref());
+    }
+
+    for (name, e) in model.process.iter().flatten() {
+        let id = &format!("process.{name}");
+        v.list(id, "risks", e.risks.as_ref());
+    }
+
+    for (name, e) in model.protocol.iter().flatten() {
+        let id = &format!("protocol.{name}");
+        v.list(id, "risks", e.risks.as_ref());
+    }
+
+    for (name, e) in model.deployment.iter().flatten() {
+        let id = &format!("deployment.{name}");
+        v.list(id, "targets", e.targets.as_ref());
+        v.list(id, "applications", e.applications.as_ref());
+        v.list(id, "stores", e.stores.as_ref());
+        v.list(id, "risks", e.risks.as_ref());
+    }
+
+    for (name, e) in model.boundary.iter().flatten() {
+        let id = &format!("boundary.{name}");
+        v.list(id, "networks", e.networks.as_ref());
+        v.list(id, "servers", e.servers.as_ref());
+        v.list(id, "endpoints", e.endpoints.as_ref());
+        v.list(id, "applications", e.applications.as_ref());
+        v.list(id, "risks", e.risks.as_ref());
+    }
+
+    for (name, e) in model.control.iter().flatten() {
+        let id = &format!("control.{name}");
+        v.list(id, "mitigates", e.mitigates.as_ref());
+        v.list(id, "remediates", e.remediates.as_ref());
+        v.list(id, "risks", e.risks.as_ref());
+    }
+
+    for (name, e) in model.acceptance.iter().flatten() {
+        let id = &format!("acceptance.{name}");
+        v.optional(id, "risk", e.risk.as_ref());
+        v.list(id, "entities", e.entities.as_ref());
+        v.optional(id, "approver", e.approver.as_ref());
+        let missing = [
+            ("risk", e.risk.is_none()),
+            ("approver", e.approver.is_none()),
+            ("granted", e.granted.is_none()),
+        ];
+        for (field, _) in missing.into_iter().filter(|(_, missing)| *missing) {
+            v.warning(id, field, format!("acceptance has no `{field}`"));
+        }
+        if let Some(expires) = e.expires {
+            if e.expired() {
+                v.warning(id, "expires", format!("acceptance expired on {expires}"));
+            }
+        }
+    }
+
+    for (name, e) in model.authentication.iter().flatten() {
+        let id = &format!("authentication.{name}");
+        v.list(id, "keys", e.keys.as_ref());
+        v.list(id, "secrets", e.secrets.as_ref());
+    }
+
+    for (name, e) in model.encryption.iter().flatten() {
+        let id = &format!("encryption.{name}");
+        v.list(id, "keys", e.keys.as_ref());
+    }
+
+    for (name, e) in model.key.iter().flatten() {
+        let id = &format!("key.{name}");
+        v.custody(id, "key", &e.custody);
+        v.list(id, "risks", e.risks.as_ref());
+    }
+
+    for (name, e) in model.secret.iter().flatten() {
+        let id = &format!("secret.{name}");
+        v.custody(id, "secret", &e.custody);
+        v.list(id, "risks", e.risks.as_ref());
+    }
+
+    for (name, e) in model.threat.iter().flatten() {
+        let id = &format!("threat.{name}");
+        v.list(id, "risk", e.risk.as_ref());
+        v.list(id, "actor", e.actor.as_ref());
+        v.list(id, "networks", e.networks.as_ref());
+    }
+
+    for (n, c) in model.comment.iter().flatten().enumerate() {
+        let id = &format!("comment[{n}]");
+        v.reference(id, "entity", &c.entity);
+    }
+
+    v.deployments();
+    v.duplicate_addresses();
+    v.overlapping_ranges();
+    v.overlapping_boundaries();
+
+    v.diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ApplicationID, NetworkID};
+
+    #[test]
+    fn dangling_references() {
+        let m = crate::load("../test/test_model.toml").unwrap();
+        let diagnostics = validate(&m);
+        assert!(diagnostics.iter().all(Diagnostic::is_error));
+        let phi = diagnostics
+            .iter()
+            .find(|d| d.entity == "data.health-info" && d.message.contains("regulation.phi"))
+            .unwrap();
+        let location = phi.location.as_ref().unwrap();
+        assert_eq!("../test/test_model.toml", location.file);
+        assert!(location.text.starts_with("regulations = "));
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.entity == "flow.website-client" && d.field == "destinations"));
+    }
+
+    #[test]
+    fn classification_below_contents() {
+        let m: Model = toml::from_str(
+            r#"
+[classification.public]
+level = 0
+[classification.secret]
+level = 2
+[data.record]
+classification = "classification.public"
+contains = ["data.ssn"]
+[data.ssn]
+classification = "classification.secret"
+"#,
+        )
+        .unwrap();
+        let diagnostics = validate(&m);
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(Severity::Warning, diagnostics[0].severity);
+        assert_eq!("data.record", diagnostics[0].entity);
+        assert!(diagnostics[0].message.contains("classification.secret"));
+    }
+
+    #[test]
+    fn acceptances() {
+        let m: Model = toml::from_str(
+            r#"
+[risk.loss]
+[actor.ciso]
+[store.db]
+
+[acceptance.current]
+risk = "risk.loss"
+entities = ["store.db"]
+approver = "actor.ciso"
+granted = 2024-01-01
+expires = "2999-12-31"
+[acceptance.lapsed]
+risk = "risk.loss"
+entities = ["store.db"]
+approver = "actor.ciso"
+granted = 2023-01-01
+expires = 2024-01-01
+[acceptance.unapproved]
+risk = "risk.loss"
+"#,
+        )
+        .unwrap();
+        let acceptance = m.acceptance_by_id("acceptance.lapsed").unwrap();
+        assert_eq!(
+            Some(chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
+            acceptance.expires
+        );
+        let diagnostics: Vec<String> = validate(&m).iter().map(ToString::to_string).collect();
+        assert_eq!(
+            vec![
+                "warning: acceptance.lapsed.expires: acceptance expired on 2024-01-01",
+                "warning: acceptance.unapproved.approver: acceptance has no `approver`",
+                "warning: acceptance.unapproved.granted: acceptance has no `granted`",
+            ],
+            diagnostics
+        );
+        assert!(toml::from_str::<Model>("[acceptance.a]\ngranted = 2024-01-01T10:00:00").is_err());
+    }
+
+    #[test]
+    fn addresses() {
+        let m: Model = toml::from_str(
+            r#"
+[network.dmz]
+ipv4_ranges = ["10.0.1.0/24"]
+ipv6_ranges = ["2001:db8:1::/48"]
+[network.prod]
+ipv4_ranges = ["10.0.2.0/24", "10.0.1.128/25"]
+ipv6_ranges = ["2001:db8:1:1::/64"]
+[network.internet]
+[network.office]
+[network.lab]
+
+[server.fw]
+interfaces = [
+    { network = "network.dmz", address = "10.0.1.1" },
+    { network = "network.prod", address = "10.0.1.1" },
+    { network = "network.internet", address = "203.0.113.7" },
+]
+[server.web]
+interfaces = [{ network = "network.dmz", address = "2001:db8:2::1" }]
+[server.nas]
+interfaces = [{ network = "network.office", address = "192.168.1.10" }]
+[server.printer]
+interfaces = [{ network = "network.office", address = "192.168.1.10" }]
+[server.pi]
+interfaces = [{ network = "network.lab", address = "192.168.1.10" }]
+"#,
+        )
+        .unwrap();
+        let diagnostics: Vec<String> = validate(&m).iter().map(ToString::to_string).collect();
+        assert_eq!(
+            vec![
+                "error: server.fw.interfaces: address 10.0.1.1 is not in the ranges of `network.prod`",
+                "error: server.web.interfaces: address 2001:db8:2::1 is not in the ranges of `network.dmz`",
+                "error: server.fw.interfaces: address 10.0.1.1 is also used by interfaces[0] of `server.fw`",
+                "error: server.printer.interfaces: address 192.168.1.10 is also used by interfaces[0] of `server.nas`",
+                "warning: network.prod.ipv4_ranges: range 10.0.1.128/25 overlaps 10.0.1.0/24 of `network.dmz`",
+                "warning: network.prod.ipv6_ranges: range 2001:db8:1:1::/64 overlaps 2001:db8:1::/48 of `network.dmz`",
+            ],
+            diagnostics
+        );
+        let address = "10.0.1.200".parse().unwrap();
+        assert_eq!(
+            Some(NetworkID::named("prod")),
+            m.network_containing(&address)
+        );
+        assert!(toml::from_str::<Model>("[network.a]\nipv4_ranges = [\"10.0.0.0/33\"]").is_err());
+        assert!(
+            toml::from_str::<Model>("[server.a]\ninterfaces = [{ address = \"10.0.0\" }]").is_err()
+        );
+    }
+
+    #[test]
+    fn overlapping_boundaries() {
+        let m: Model = toml::from_str(
+            r#"
+[network.dmz]
+[server.web]
+[boundary.perimeter]
+networks = ["network.dmz"]
+[boundary.secure]
+networks = ["network.dmz"]
+servers = ["server.web"]
+"#,
+        )
+        .unwrap();
+        let diagnostics: Vec<String> = validate(&m).iter().map(ToString::to_string).collect();
+        assert_eq!(
+            vec![
+                "warning: boundary.secure.networks: `network.dmz` is also in `boundary.perimeter`"
+            ],
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn deployments() {
+        let m: Model = toml::from_str(
+            r#"
+[server.web1]
+applications = ["application.site"]
+[server.web2]
+[server.old]
+applications = ["application.site"]
+[server.worker]
+[application.site]
+[application.jobs]
+[deployment.site]
+title = "Site"
+targets = ["server.web1", "server.web2"]
+applications = ["application.site"]
+[deployment.jobs]
+targets = ["server.worker"]
+applications = ["application.jobs"]
+"#,
+        )
+        .unwrap();
+        let diagnostics: Vec<String> = validate(&m).iter().map(ToString::to_string).collect();
+        // no server lists the jobs, so their deployment says where they run
+        assert_eq!(
+            vec![
+                "warning: server.old.applications: lists `application.site`, which no deployment puts on it",
+                "warning: deployment.site.targets: puts `application.site` on `server.web2`, which does not list it",
+            ],
+            diagnostics
+        );
+        assert_eq!(
+            vec![ApplicationID::named("site")],
+            m.host_applications("server.web2")
+        );
+        assert_eq!(
+            vec![ApplicationID::named("jobs")],
+            m.host_applications("server.worker")
+        );
+    }
+}
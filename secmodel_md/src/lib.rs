@@ -25,25 +25,86 @@ pub fn section_header(level: usize, title: &str, anchor: &str) -> String {
 }
 
 pub fn entity_link(id: &str, model: &Model) -> String {
-    if let Some(e) = model.entity_by_id(id) {
-        local_link(&e.get_title(id), id)
-    } else {
-        format!("{id}")
+    match model.entity_by_id(id) {
+        Ok(e) => local_link(&e.get_title(id), id),
+        Err(e) => {
+            eprintln!("warning: {e}");
+            id.to_string()
+        }
     }
 }
 
-pub fn entity_links(ids: &[String], label: &str, model: &Model) -> String {
+pub fn entity_links<T: AsRef<str>>(ids: &[T], label: &str, model: &Model) -> String {
     let mut text = String::new();
     for id in ids {
-        text.push_str(&format!("* {}\n", entity_link(id, model)));
+        text.push_str(&format!("* {}\n", entity_link(id.as_ref(), model)));
     }
-    if text.len() > 0 {
+    if !text.is_empty() {
         format!("{label}:\n\n{text}\n")
     } else {
         text
     }
 }
 
+// the most restrictive classification of the data an entity holds
+pub fn classification_item(id: &str, model: &Model) -> String {
+    match model.effective_classification(id) {
+        Some(class) => format!("* Classification: {}\n", entity_link(&class, model)),
+        None => String::new(),
+    }
+}
+
+// The risks of entities, and how each is treated by their controls, or
+// accepted.
+pub fn risk_register_table(entries: &[RegisterEntry], with_entity: bool, model: &Model) -> String {
+    let mut text = String::new();
+    if with_entity {
+        text.push_str("| Entity | Risk | Treatment | Treated By |\n");
+        text.push_str("|--------|------|-----------|------------|\n");
+    } else {
+        text.push_str("| Risk | Treatment | Treated By |\n");
+        text.push_str("|------|-----------|------------|\n");
+    }
+    for entry in entries {
+        if with_entity {
+            text.push_str(&format!("| {} ", entity_link(&entry.entity, model)));
+        }
+        let treated_by: Vec<String> = entry
+            .controls
+            .iter()
+            .map(|c| entity_link(c, model))
+            .chain(entry.acceptances.iter().map(|a| entity_link(a, model)))
+            .collect();
+        text.push_str(&format!(
+            "| {} | {} | {} |\n",
+            entity_link(&entry.risk, model),
+            entry.treatment,
+            treated_by.join(", ")
+        ));
+    }
+    text.push('\n');
+    text
+}
+
+pub fn entity_risks_table(id: &str, model: &Model) -> String {
+    let entries = entity_risks(model, id);
+    if entries.is_empty() {
+        return String::new();
+    }
+    format!("Risks:\n\n{}", risk_register_table(&entries, false, model))
+}
+
+pub fn risk_register_section(model: &Model) -> String {
+    let entries = risk_register(model);
+    if entries.is_empty() {
+        return String::new();
+    }
+    format!(
+        "# Risk Register\n\n{}",
+        risk_register_table(&entries, true, model)
+    )
+}
+
 pub fn interfaces_list(interfaces: &[NetworkInterface], model: &Model) -> String {
     let mut text = String::new();
     for i in interfaces {
@@ -89,16 +150,81 @@ impl DetailSection for Network {
             model,
         ));
 
-        // risks
-        if let Some(risks) = &self.risks {
-            text.push_str(&entity_links(risks, "Risks", model));
+        if let Some(controls) = &self.controls {
+            text.push_str(&network_controls_section(id, controls, model));
         }
 
+        // risks, and how they are treated
+        text.push_str(&entity_risks_table(id, model));
+
         //let comments = model.entity_comments(id);
         text
     }
 }
 
+// the links to the entities, or `none` when there is no list
+fn links_cell<T: AsRef<str>>(ids: Option<&Vec<T>>, none: &str, model: &Model) -> String {
+    match ids {
+        Some(ids) => ids
+            .iter()
+            .map(|i| entity_link(i.as_ref(), model))
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => none.to_string(),
+    }
+}
+
+// The defaults and rules for traffic in to and out of a network.  A rule
+// not limited to particular flows, sources or destinations applies to any.
+pub fn network_controls_section(id: &str, controls: &NetworkControls, model: &Model) -> String {
+    let mut text = section_header(4, "Network Controls", &format!("{id}-controls"));
+    if let Some(description) = &controls.description {
+        text.push_str(&format!(
+            "{description}
+
+"
+        ));
+    }
+    let default = |d: Option<FlowControlAction>| d.unwrap_or(FlowControlAction::Allow);
+    text.push_str(&format!(
+        "* Ingress Default: {}\n",
+        default(controls.ingress_default)
+    ));
+    text.push_str(&format!(
+        "* Egress Default: {}\n\n",
+        default(controls.egress_default)
+    ));
+    for (label, rules) in [("Ingress", &controls.ingress), ("Egress", &controls.egress)] {
+        let Some(rules) = rules.as_ref().filter(|r| !r.is_empty()) else {
+            continue;
+        };
+        text.push_str(&format!("{label}:\n\n"));
+        text.push_str(
+            "| Action | Flows | Sources | Destinations | Controller | Mitigates | Remediates |\n",
+        );
+        text.push_str(
+            "|--------|-------|---------|--------------|------------|-----------|------------|\n",
+        );
+        for rule in rules {
+            text.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} | {} |\n",
+                rule.action.unwrap_or(FlowControlAction::Allow),
+                links_cell(rule.flows.as_ref(), "any", model),
+                links_cell(rule.sources.as_ref(), "any", model),
+                links_cell(rule.destinations.as_ref(), "any", model),
+                rule.controller
+                    .as_ref()
+                    .map(|c| entity_link(c, model))
+                    .unwrap_or_default(),
+                links_cell(rule.mitigates.as_ref(), "", model),
+                links_cell(rule.remediates.as_ref(), "", model),
+            ));
+        }
+        text.push('\n');
+    }
+    text
+}
+
 impl DetailSection for Server {
     fn detail_section(&self, id: &str, model: &Model) -> String {
         let mut text = String::new();
@@ -115,21 +241,24 @@ impl DetailSection for Server {
         if let Some(version) = &self.version {
             text.push_str(&format!("* Version: {version}\n"));
         }
-        text.push_str("\n");
+        text.push_str(&classification_item(id, model));
+        text.push('\n');
         // Interfaces
         if let Some(interfaces) = &self.interfaces {
             text.push_str(&interfaces_list(interfaces, model));
         }
 
-        // applications
-        if let Some(apps) = &self.applications {
-            text.push_str(&entity_links(apps, "Hosted Applications", model));
-        }
-
-        // stores
-        if let Some(stores) = &self.stores {
-            text.push_str(&entity_links(stores, "Hosted Stores", model));
-        }
+        // applications and stores, including those deployed to it
+        text.push_str(&entity_links(
+            &model.host_applications(id),
+            "Hosted Applications",
+            model,
+        ));
+        text.push_str(&entity_links(
+            &model.host_stores(id),
+            "Hosted Stores",
+            model,
+        ));
         //flows
         text.push_str(&entity_links(
             &model.entity_flows(id),
@@ -137,15 +266,32 @@ impl DetailSection for Server {
             model,
         ));
 
-        // risks
-        if let Some(risks) = &self.risks {
-            text.push_str(&entity_links(risks, "Risks", model));
-        }
+        // risks, and how they are treated
+        text.push_str(&entity_risks_table(id, model));
 
         text
     }
 }
 
+impl DetailSection for Deployment {
+    fn detail_section(&self, id: &str, model: &Model) -> String {
+        let mut text = String::new();
+        text.push_str(&section_header(3, &self.get_title(id), id));
+        text.push_str(&format!("{}\n\n", self.get_description()));
+        if let Some(targets) = &self.targets {
+            text.push_str(&entity_links(targets, "Targets", model));
+        }
+        if let Some(applications) = &self.applications {
+            text.push_str(&entity_links(applications, "Applications", model));
+        }
+        if let Some(stores) = &self.stores {
+            text.push_str(&entity_links(stores, "Stores", model));
+        }
+        text.push_str(&entity_risks_table(id, model));
+        text
+    }
+}
+
 impl DetailSection for Endpoint {
     fn detail_section(&self, id: &str, model: &Model) -> String {
         let mut text = String::new();
@@ -162,7 +308,8 @@ impl DetailSection for Endpoint {
         if let Some(version) = &self.version {
             text.push_str(&format!("* Version: {version}\n"));
         }
-        text.push_str("\n");
+        text.push_str(&classification_item(id, model));
+        text.push('\n');
         // Interfaces
         if let Some(interfaces) = &self.interfaces {
             text.push_str(&interfaces_list(interfaces, model));
@@ -179,10 +326,8 @@ impl DetailSection for Endpoint {
             model,
         ));
 
-        // risks
-        if let Some(risks) = &self.risks {
-            text.push_str(&entity_links(risks, "Risks", model));
-        }
+        // risks, and how they are treated
+        text.push_str(&entity_risks_table(id, model));
 
         text
     }
@@ -201,10 +346,8 @@ impl DetailSection for Application {
             model,
         ));
 
-        // risks
-        if let Some(risks) = &self.risks {
-            text.push_str(&entity_links(risks, "Risks", model));
-        }
+        // risks, and how they are treated
+        text.push_str(&entity_risks_table(id, model));
 
         //controls
         if let Some(controls) = &self.controls {
@@ -228,10 +371,8 @@ impl DetailSection for Actor {
             model,
         ));
 
-        // risks
-        if let Some(risks) = &self.risks {
-            text.push_str(&entity_links(risks, "Risks", model));
-        }
+        // risks, and how they are treated
+        text.push_str(&entity_risks_table(id, model));
 
         text
     }
@@ -259,7 +400,7 @@ impl DetailSection for Agent {
             text.push_str(&format!("* Process: {}\n", entity_link(eid, model)));
         }
 
-        text.push_str("\n");
+        text.push('\n');
 
         //flows
         text.push_str(&entity_links(
@@ -268,10 +409,8 @@ impl DetailSection for Agent {
             model,
         ));
 
-        // risks
-        if let Some(risks) = &self.risks {
-            text.push_str(&entity_links(risks, "Risks", model));
-        }
+        // risks, and how they are treated
+        text.push_str(&entity_risks_table(id, model));
 
         text
     }
@@ -284,8 +423,9 @@ impl DetailSection for Flow {
         text.push_str(&format!("{}\n\n", self.get_description()));
         if let Some(channel) = &self.channel {
             text.push_str(&format!("* Channel: {}\n", &entity_link(channel, model)));
-            text.push_str(&format!("\n"));
         }
+        text.push_str(&classification_item(id, model));
+        text.push('\n');
 
         if let Some(sources) = &self.sources {
             text.push_str(&entity_links(sources, "Sources", model));
@@ -299,9 +439,8 @@ impl DetailSection for Flow {
             text.push_str(&entity_links(data, "Data", model));
         }
 
-        if let Some(risks) = &self.risks {
-            text.push_str(&entity_links(risks, "Risks", model));
-        }
+        // risks, and how they are treated
+        text.push_str(&entity_risks_table(id, model));
 
         text
     }
@@ -343,7 +482,7 @@ impl DetailSection for Channel {
             ));
         }
 
-        text.push_str(&format!("\n"));
+        text.push('\n');
 
         if let Some(protocols) = &self.protocols {
             text.push_str(&entity_links(protocols, "Protocols", model));
@@ -353,9 +492,8 @@ impl DetailSection for Channel {
             text.push_str(&entity_links(over, "Runs on Channels:", model));
         }
 
-        if let Some(risks) = &self.risks {
-            text.push_str(&entity_links(risks, "Risks", model));
-        }
+        // risks, and how they are treated
+        text.push_str(&entity_risks_table(id, model));
 
         if let Some(controls) = &self.controls {
             text.push_str(&entity_links(controls, "Controls", model));
@@ -378,12 +516,19 @@ impl DetailSection for Data {
         text.push_str(&section_header(3, &self.get_title(id), id));
         text.push_str(&format!("{}\n\n", self.get_description()));
         // owner/steward/format/classification
+        let effective = model.effective_classification(id);
         if let Some(class) = &self.classification {
             text.push_str(&format!(
                 "* Classification: {}\n",
                 &entity_link(class, model)
             ));
         }
+        if let Some(effective) = effective.filter(|e| Some(e) != self.classification.as_ref()) {
+            text.push_str(&format!(
+                "* Effective Classification: {} (from the data it contains)\n",
+                &entity_link(&effective, model)
+            ));
+        }
         if let Some(format) = &self.format {
             text.push_str(&format!("* Format: {}\n", &entity_link(format, model)));
         }
@@ -394,7 +539,7 @@ impl DetailSection for Data {
             text.push_str(&format!("* Steward: {}\n", &entity_link(steward, model)));
         }
 
-        text.push_str("\n");
+        text.push('\n');
 
         if let Some(data) = &self.contains {
             text.push_str(&entity_links(data, "Contains", model));
@@ -412,9 +557,8 @@ impl DetailSection for Data {
             text.push_str(&entity_links(controls, "Controls", model));
         }
 
-        if let Some(risks) = &self.risks {
-            text.push_str(&entity_links(risks, "Risks", model));
-        }
+        // risks, and how they are treated
+        text.push_str(&entity_risks_table(id, model));
 
         //flows
         text.push_str(&entity_links(&model.entity_flows(id), "Data Flows", model));
@@ -422,6 +566,18 @@ impl DetailSection for Data {
         //stores
         text.push_str(&entity_links(&model.data_stores(id), "Data Stores", model));
 
+        //everywhere the data ends up
+        if let Ok(lineage) = lineage(model, id) {
+            let mut lists = String::new();
+            for (label, ids) in lineage.entities() {
+                lists.push_str(&entity_links(&ids, label, model));
+            }
+            if !lists.is_empty() {
+                text.push_str(&section_header(4, "Lineage", &format!("{id}-lineage")));
+                text.push_str(&lists);
+            }
+        }
+
         text
     }
 }
@@ -443,10 +599,7 @@ impl DetailSection for Store {
         }
 
         if let Some(backup) = &self.backup {
-            text.push_str(&format!(
-                "* Backup Scheme: {}\n",
-                &entity_link(backup, model)
-            ));
+            text.push_str(&format!("* Backup: {}\n", &entity_link(backup, model)));
         }
 
         if let Some(authentication) = &self.authentication {
@@ -467,8 +620,9 @@ impl DetailSection for Store {
                 &entity_link(encryption, model)
             ));
         }
+        text.push_str(&classification_item(id, model));
 
-        text.push_str("\n");
+        text.push('\n');
 
         if let Some(data) = &self.data {
             text.push_str(&entity_links(data, "Data", model));
@@ -485,42 +639,178 @@ impl DetailSection for Store {
             model,
         ));
 
-        // risks
-        if let Some(risks) = &self.risks {
-            text.push_str(&entity_links(risks, "Risks", model));
-        }
+        // risks, and how they are treated
+        text.push_str(&entity_risks_table(id, model));
+
+        text
+    }
+}
 
+impl DetailSection for Backup {
+    fn detail_section(&self, id: &str, model: &Model) -> String {
+        let mut text = String::new();
+        text.push_str(&section_header(3, &self.get_title(id), id));
+        text.push_str(&format!("{}\n\n", self.get_description()));
+        text.push_str(&attributes_table(&[
+            (
+                "Target",
+                self.target.as_ref().map(|t| entity_link(t, model)),
+            ),
+            ("Frequency", self.frequency.map(|f| f.to_string())),
+            (
+                "Retention",
+                self.retention_days.map(|d| format!("{d} days")),
+            ),
+            ("Offsite", self.offsite.map(yes_no)),
+            (
+                "Encryption",
+                self.encryption.as_ref().map(|e| entity_link(e, model)),
+            ),
+            (
+                "Restore Verified",
+                Some(self.verified.map_or("never".to_string(), |v| v.to_string())),
+            ),
+        ]));
+        let stores = model.graph().reverse_neighbors(id, Relation::BackedUpBy);
+        text.push_str(&entity_links(&stores, "Backs up", model));
+        text.push_str(&entity_risks_table(id, model));
         text
     }
 }
 
+// A table of the attributes given, leaving out those which are not.
+pub fn attributes_table(rows: &[(&str, Option<String>)]) -> String {
+    let mut text = String::new();
+    for (name, value) in rows {
+        if let Some(value) = value {
+            text.push_str(&format!("| {name} | {value} |\n"));
+        }
+    }
+    if text.is_empty() {
+        return text;
+    }
+    format!("| Attribute | Value |\n|-----------|-------|\n{text}\n")
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "Yes" } else { "No" }.to_string()
+}
+
 impl DetailSection for Authentication {
-    fn detail_section(&self, id: &str, _model: &Model) -> String {
+    fn detail_section(&self, id: &str, model: &Model) -> String {
         let mut text = String::new();
         text.push_str(&section_header(3, &self.get_title(id), id));
         text.push_str(&format!("{}\n\n", self.get_description()));
+        let factors = self.factors.as_ref().map(|factors| {
+            factors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        });
+        text.push_str(&attributes_table(&[
+            ("Multi-Factor", self.multi_factor().map(yes_no)),
+            ("Factors", factors),
+            ("Phishing Resistant", self.phishing_resistant.map(yes_no)),
+        ]));
+        if let Some(keys) = &self.keys {
+            text.push_str(&entity_links(keys, "Keys", model));
+        }
+        if let Some(secrets) = &self.secrets {
+            text.push_str(&entity_links(secrets, "Secrets", model));
+        }
+        text.push_str(&entity_links(&scheme_users(model, id), "Used by", model));
         text
     }
 }
 
 impl DetailSection for Authorization {
-    fn detail_section(&self, id: &str, _model: &Model) -> String {
+    fn detail_section(&self, id: &str, model: &Model) -> String {
         let mut text = String::new();
         text.push_str(&section_header(3, &self.get_title(id), id));
         text.push_str(&format!("{}\n\n", self.get_description()));
+        text.push_str(&attributes_table(&[(
+            "Model",
+            self.model.map(|m| m.to_string()),
+        )]));
+        text.push_str(&entity_links(&scheme_users(model, id), "Used by", model));
         text
     }
 }
 
 impl DetailSection for Encryption {
-    fn detail_section(&self, id: &str, _model: &Model) -> String {
+    fn detail_section(&self, id: &str, model: &Model) -> String {
         let mut text = String::new();
         text.push_str(&section_header(3, &self.get_title(id), id));
         text.push_str(&format!("{}\n\n", self.get_description()));
+        text.push_str(&attributes_table(&[
+            ("Algorithms", self.algorithms.as_ref().map(|a| a.join(", "))),
+            ("Key Length", self.key_length.map(|l| format!("{l} bits"))),
+            (
+                "Protocol Versions",
+                self.protocol_versions.as_ref().map(|v| v.join(", ")),
+            ),
+            (
+                "Key Management",
+                self.key_management
+                    .as_ref()
+                    .map(|k| k.trim().replace('\n', " ")),
+            ),
+        ]));
+        if let Some(keys) = &self.keys {
+            text.push_str(&entity_links(keys, "Keys", model));
+        }
         text
     }
 }
 
+// Where a key or secret is held, who owns it and when it is to be rotated,
+// and what uses it.
+fn custody_section<E: Entity>(entity: &E, custody: &Custody, id: &str, model: &Model) -> String {
+    let mut text = String::new();
+    text.push_str(&section_header(3, &entity.get_title(id), id));
+    text.push_str(&format!("{}\n\n", entity.get_description()));
+    let mut items = String::new();
+    if let Some(store) = &custody.store {
+        items.push_str(&format!("* Held in: {}\n", entity_link(store, model)));
+    }
+    if let Some(server) = &custody.server {
+        items.push_str(&format!("* Held by: {}\n", entity_link(server, model)));
+    }
+    if let Some(owner) = &custody.owner {
+        items.push_str(&format!("* Owner: {}\n", entity_link(owner, model)));
+    }
+    if let Some(days) = custody.rotation_days {
+        items.push_str(&format!("* Rotated every: {days} days\n"));
+    }
+    if let Some(rotated) = custody.rotated {
+        if custody.rotation_overdue() {
+            items.push_str(&format!("* Last rotated: {rotated} (**overdue**)\n"));
+        } else {
+            items.push_str(&format!("* Last rotated: {rotated}\n"));
+        }
+    }
+    if !items.is_empty() {
+        text.push_str(&format!("{items}\n"));
+    }
+    let used_by = model.graph().reverse_neighbors(id, Relation::Uses);
+    text.push_str(&entity_links(&used_by, "Used by", model));
+    text.push_str(&entity_risks_table(id, model));
+    text
+}
+
+impl DetailSection for Key {
+    fn detail_section(&self, id: &str, model: &Model) -> String {
+        custody_section(self, &self.custody, id, model)
+    }
+}
+
+impl DetailSection for Secret {
+    fn detail_section(&self, id: &str, model: &Model) -> String {
+        custody_section(self, &self.custody, id, model)
+    }
+}
+
 impl DetailSection for Control {
     fn detail_section(&self, id: &str, _model: &Model) -> String {
         let mut text = String::new();
@@ -531,19 +821,137 @@ impl DetailSection for Control {
 }
 
 impl DetailSection for Risk {
-    fn detail_section(&self, id: &str, _model: &Model) -> String {
+    fn detail_section(&self, id: &str, model: &Model) -> String {
+        let mut text = String::new();
+        text.push_str(&section_header(3, &self.get_title(id), id));
+        text.push_str(&format!("{}\n\n", self.get_description()));
+        let score = id.parse().ok().and_then(|risk| score_risk(model, &risk));
+        if let Some(score) = score {
+            text.push_str(&format!("* Likelihood: {}\n", score.likelihood));
+            text.push_str(&format!("* Impact: {}\n", score.impact));
+            text.push_str(&format!("* Inherent Risk: {:.1}\n", score.inherent));
+            text.push_str(&format!("* Residual Risk: {:.1}\n", score.residual));
+            if score.accepted {
+                text.push_str("* Accepted\n");
+            }
+            if let Some(loss) = score.annual_loss {
+                text.push_str(&format!("* Annual Loss Expectancy: {loss:.0}\n"));
+            }
+            text.push('\n');
+            text.push_str(&entity_links(&score.controls, "Controls", model));
+        }
+        text
+    }
+}
+
+impl DetailSection for Acceptance {
+    fn detail_section(&self, id: &str, model: &Model) -> String {
         let mut text = String::new();
         text.push_str(&section_header(3, &self.get_title(id), id));
         text.push_str(&format!("{}\n\n", self.get_description()));
+        let mut items = String::new();
+        if let Some(risk) = &self.risk {
+            items.push_str(&format!("* Risk: {}\n", entity_link(risk, model)));
+        }
+        if let Some(approver) = &self.approver {
+            items.push_str(&format!(
+                "* Approved by: {}\n",
+                entity_link(approver, model)
+            ));
+        }
+        if let Some(granted) = self.granted {
+            items.push_str(&format!("* Granted: {granted}\n"));
+        }
+        match self.expires {
+            Some(expires) if self.expired() => {
+                items.push_str(&format!("* Expires: {expires} (**expired**)\n"));
+            }
+            Some(expires) => items.push_str(&format!("* Expires: {expires}\n")),
+            None => items.push_str("* Expires: never\n"),
+        }
+        text.push_str(&format!("{items}\n"));
+        if let Some(rationale) = &self.rationale {
+            text.push_str(&format!("Rationale:\n\n{rationale}\n\n"));
+        }
+        if let Some(entities) = &self.entities {
+            text.push_str(&entity_links(entities, "Applies to", model));
+        }
         text
     }
 }
 
+impl DetailSection for Boundary {
+    fn detail_section(&self, id: &str, model: &Model) -> String {
+        let mut text = String::new();
+        text.push_str(&section_header(3, &self.get_title(id), id));
+        text.push_str(&format!("{}\n\n", self.get_description()));
+        if let Some(trust) = self.trust {
+            text.push_str(&format!("* Trust: {trust}\n\n"));
+        }
+        if let Some(networks) = &self.networks {
+            text.push_str(&entity_links(networks, "Networks", model));
+        }
+        if let Some(servers) = &self.servers {
+            text.push_str(&entity_links(servers, "Servers", model));
+        }
+        if let Some(endpoints) = &self.endpoints {
+            text.push_str(&entity_links(endpoints, "Endpoints", model));
+        }
+        if let Some(applications) = &self.applications {
+            text.push_str(&entity_links(applications, "Applications", model));
+        }
+        text.push_str(&entity_risks_table(id, model));
+        text
+    }
+}
+
+// where a threat acts from, and what it can reach
+pub fn attack_surface_section(surface: &AttackSurface, model: &Model) -> String {
+    let id = &surface.threat;
+    let mut text = section_header(4, "Attack Surface", &format!("{id}-surface"));
+    if surface.positions.is_empty() {
+        text.push_str("The networks this threat acts from are not known.\n\n");
+    } else {
+        text.push_str(&entity_links(&surface.positions, "Acts from", model));
+        text.push_str(&entity_links(&surface.networks, "Reaches", model));
+    }
+    if surface.exposed.is_empty() {
+        text.push_str("No reachable entities are exposed to this threat.\n\n");
+    } else {
+        text.push_str(&entity_links(&surface.exposed, "Exposed", model));
+    }
+    text.push_str(&entity_links(
+        &surface.unreachable,
+        "Exposed, but not reachable",
+        model,
+    ));
+    text
+}
+
 impl DetailSection for Threat {
-    fn detail_section(&self, id: &str, _model: &Model) -> String {
+    fn detail_section(&self, id: &str, model: &Model) -> String {
         let mut text = String::new();
         text.push_str(&section_header(3, &self.get_title(id), id));
         text.push_str(&format!("{}\n\n", self.get_description()));
+        let mut items = String::new();
+        if let Some(sophistication) = self.sophistication {
+            items.push_str(&format!("* Sophistication: {sophistication}\n"));
+        }
+        if let Some(capability) = self.capability {
+            items.push_str(&format!("* Capability: {capability}\n"));
+        }
+        if !items.is_empty() {
+            text.push_str(&format!("{items}\n"));
+        }
+        if let Some(actors) = &self.actor {
+            text.push_str(&entity_links(actors, "Actors", model));
+        }
+        if let Some(risks) = &self.risk {
+            text.push_str(&entity_links(risks, "Risks", model));
+        }
+        if let Ok(surface) = attack_surface(model, id) {
+            text.push_str(&attack_surface_section(&surface, model));
+        }
         text
     }
 }
@@ -553,6 +961,9 @@ impl DetailSection for Classification {
         let mut text = String::new();
         text.push_str(&section_header(3, &self.get_title(id), id));
         text.push_str(&format!("{}\n\n", self.get_description()));
+        if let Some(level) = self.level {
+            text.push_str(&format!("* Level: {level}\n\n"));
+        }
         text
     }
 }
@@ -585,12 +996,175 @@ fn entity_section(
     text.push_str(&format!("## {title}\n\n"));
     for (id, e) in entities.iter() {
         let id = &format!("{entity}.{id}");
-        text.push_str(&e.detail_section(&id, model));
+        text.push_str(&e.detail_section(id, model));
+        if model.sources.file_count() > 1 {
+            if let Some(file) = model.entity_file(id) {
+                text.push_str(&format!("Defined in: `{file}`\n"));
+            }
+        }
         text.push_str("\n----\n\n");
     }
     text
 }
 
+pub fn findings_section(findings: &[Finding], model: &Model) -> String {
+    let mut text = String::new();
+    for finding in findings {
+        let entities: Vec<String> = finding
+            .entities
+            .iter()
+            .map(|id| entity_link(id, model))
+            .collect();
+        text.push_str(&format!(
+            "* **{}**: {} ({})\n",
+            finding.severity,
+            finding.message,
+            entities.join(", ")
+        ));
+    }
+    if text.is_empty() {
+        text.push_str("No findings.\n");
+    }
+    format!("# Findings\n\n{text}\n")
+}
+
+fn risk_scores_table(scores: &[&RiskScore], model: &Model) -> String {
+    let mut text = String::new();
+    text.push_str("| Risk | Likelihood | Impact | Inherent | Residual | Controls |\n");
+    text.push_str("|------|------------|--------|----------|----------|----------|\n");
+    for score in scores {
+        text.push_str(&format!(
+            "| {} | {} | {} | {:.1} | {:.1} | {} |\n",
+            entity_link(&score.risk, model),
+            score.likelihood,
+            score.impact,
+            score.inherent,
+            score.residual,
+            score.controls.len()
+        ));
+    }
+    text.push('\n');
+    text
+}
+
+// The rated risks, highest residual risk first, and a heat map placing
+// them by likelihood and impact before any controls are applied.
+pub fn risk_assessment_section(model: &Model) -> String {
+    let scores = score_risks(model);
+    if scores.is_empty() {
+        return String::new();
+    }
+    let mut text = String::from("# Risk Assessment\n\n");
+    let (accepted, open): (Vec<&RiskScore>, Vec<&RiskScore>) =
+        scores.iter().partition(|s| s.accepted);
+    if !open.is_empty() {
+        text.push_str(&risk_scores_table(&open, model));
+    }
+    // accepted risks are not expected to be reduced further, so are kept
+    // apart from those still to be dealt with
+    if !accepted.is_empty() {
+        text.push_str("Accepted risks:\n\n");
+        text.push_str(&risk_scores_table(&accepted, model));
+    }
+
+    text.push_str("Risks by likelihood and impact, before controls:\n\n");
+    text.push_str("| Impact \\ Likelihood |");
+    for likelihood in Rating::ALL {
+        text.push_str(&format!(" {likelihood} |"));
+    }
+    text.push_str("\n|---|");
+    text.push_str(&"---|".repeat(Rating::ALL.len()));
+    text.push('\n');
+    for impact in Rating::ALL.iter().rev() {
+        text.push_str(&format!("| **{impact}** |"));
+        for likelihood in Rating::ALL {
+            let count = scores
+                .iter()
+                .filter(|s| s.likelihood == likelihood && s.impact == *impact)
+                .count();
+            if count == 0 {
+                text.push_str("  |");
+            } else {
+                text.push_str(&format!(" {count} |"));
+            }
+        }
+        text.push('\n');
+    }
+    text.push('\n');
+    text
+}
+
+// Every flow crossing a trust boundary, and how it is protected.
+pub fn boundary_crossings_section(model: &Model) -> String {
+    let crossings = boundary_crossings(model);
+    if crossings.is_empty() {
+        return String::new();
+    }
+    let optional = |id: Option<&str>| id.map(|id| entity_link(id, model)).unwrap_or_default();
+    let mut text = String::from("# Boundary Crossings\n\n");
+    text.push_str("| Flow | From | To | Boundaries | Channel | Authentication | Encryption | Classification |\n");
+    text.push_str("|------|------|----|------------|---------|----------------|------------|----------------|\n");
+    for c in &crossings {
+        let boundaries: Vec<String> = c.boundaries.iter().map(|b| entity_link(b, model)).collect();
+        text.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} | {} |\n",
+            entity_link(&c.flow, model),
+            entity_link(&c.source, model),
+            entity_link(&c.destination, model),
+            boundaries.join(", "),
+            optional(c.channel.as_deref()),
+            optional(c.authentication.as_deref()),
+            optional(c.encryption.as_deref()),
+            optional(c.classification.as_deref()),
+        ));
+    }
+    text.push('\n');
+    text
+}
+
+// Whether each server and endpoint can reach every other, and through
+// which controllers.
+pub fn reachability_section(model: &Model) -> String {
+    let matrix = reachability(model);
+    if matrix.hosts.len() < 2 {
+        return String::new();
+    }
+    let mut text = String::from("# Network Reachability\n\n");
+    text.push_str("Whether the host of each row can reach the host of each column, and the firewalls allowing it.\n\n");
+    text.push_str("| From \\ To |");
+    for host in &matrix.hosts {
+        text.push_str(&format!(" {} |", entity_link(host, model)));
+    }
+    text.push_str("\n|---|");
+    text.push_str(&"---|".repeat(matrix.hosts.len()));
+    text.push('\n');
+    for (from, routes) in matrix.hosts.iter().zip(&matrix.routes) {
+        text.push_str(&format!("| **{}** |", entity_link(from, model)));
+        for (to, route) in matrix.hosts.iter().zip(routes) {
+            let cell = match route {
+                _ if from == to => "-".to_string(),
+                None => "no".to_string(),
+                Some(route) => {
+                    let controllers: Vec<String> = route
+                        .controllers()
+                        .iter()
+                        .map(|c| entity_link(c, model))
+                        .collect();
+                    if controllers.is_empty() {
+                        "yes".to_string()
+                    } else {
+                        format!("via {}", controllers.join(", "))
+                    }
+                }
+            };
+            text.push_str(&format!(" {cell} |"));
+        }
+        text.push('\n');
+    }
+    text.push('\n');
+    text
+}
+
 impl Report for Model {
     fn report(&self, model: &Model) -> String {
         let mut text = String::new();
@@ -610,9 +1184,15 @@ header-includes: |
         text.push_str(header);
         text.push_str("# Overview\n\n");
 
-        text.push_str(&format!("```mermaid\n"));
+        text.push_str("```mermaid\n");
         text.push_str(&secmodel_mermaid::Render::render(self, "", model).unwrap());
-        text.push_str(&format!("```\n\n"));
+        text.push_str("```\n\n");
+
+        text.push_str(&findings_section(&evaluate(model), model));
+        text.push_str(&risk_assessment_section(model));
+        text.push_str(&risk_register_section(model));
+        text.push_str(&reachability_section(model));
+        text.push_str(&boundary_crossings_section(model));
 
         text.push_str("# Entities\n\n");
 
@@ -621,11 +1201,31 @@ header-includes: |
             text.push_str(&entity_section("network", "Networks", networks, model));
         };
 
+        // trust boundaries
+        if let Some(boundaries) = &self.boundary {
+            text.push_str(&entity_section(
+                "boundary",
+                "Trust Boundaries",
+                boundaries,
+                model,
+            ));
+        };
+
         // servers
         if let Some(servers) = &self.server {
             text.push_str(&entity_section("server", "Servers", servers, model));
         };
 
+        // deployments
+        if let Some(deployments) = &self.deployment {
+            text.push_str(&entity_section(
+                "deployment",
+                "Deployments",
+                deployments,
+                model,
+            ));
+        };
+
         // endpoints
         if let Some(endpoints) = &self.endpoint {
             text.push_str(&entity_section("endpoint", "Endpoints", endpoints, model));
@@ -691,6 +1291,14 @@ header-includes: |
             ));
         };
 
+        // Keys and Secrets
+        if let Some(keys) = &self.key {
+            text.push_str(&entity_section("key", "Keys", keys, model));
+        };
+        if let Some(secrets) = &self.secret {
+            text.push_str(&entity_section("secret", "Secrets", secrets, model));
+        };
+
         // Data
         if let Some(datas) = &self.data {
             text.push_str(&entity_section("data", "Data Types", datas, model));
@@ -701,6 +1309,11 @@ header-includes: |
             text.push_str(&entity_section("store", "Data Stores", stores, model));
         };
 
+        // Backups
+        if let Some(backups) = &self.backup {
+            text.push_str(&entity_section("backup", "Backups", backups, model));
+        };
+
         // Protocols
         if let Some(protocols) = &self.protocol {
             text.push_str(&entity_section("protocol", "Protocols", protocols, model));
@@ -731,6 +1344,16 @@ header-includes: |
             text.push_str(&entity_section("risk", "Risks", risks, model));
         };
 
+        // Acceptances
+        if let Some(acceptances) = &self.acceptance {
+            text.push_str(&entity_section(
+                "acceptance",
+                "Risk Acceptances",
+                acceptances,
+                model,
+            ));
+        };
+
         // Threats
         if let Some(threats) = &self.threat {
             text.push_str(&entity_section("threat", "Threats", threats, model));
@@ -759,4 +1382,112 @@ mod tests {
         let m = secmodel_core::load("../test/test_model.toml").unwrap();
         Report::report(&m, &m);
     }
+
+    #[test]
+    fn risk_assessment() {
+        let m: Model = toml::from_str(
+            r#"
+[risk.phishing]
+title = "Phishing"
+likelihood = "high"
+impact = "medium"
+[risk.outage]
+likelihood = "low"
+impact = "very-high"
+[control.mfa]
+mitigates = ["risk.phishing"]
+"#,
+        )
+        .unwrap();
+        let text = risk_assessment_section(&m);
+        assert!(text.contains(
+            "| [risk.outage](#risk.outage) | Low | Very High | 10.0 | 10.0 | 0 |\n\
+             | [Phishing](#risk.phishing) | High | Medium | 12.0 | 6.0 | 1 |"
+        ));
+        assert!(text.contains("| **Very High** |  | 1 |  |  |  |\n"));
+        assert!(text.contains("| **Medium** |  |  |  | 1 |  |\n"));
+    }
+
+    #[test]
+    fn network_controls() {
+        let m: Model = toml::from_str(
+            r#"
+[network.prod]
+controls = { ingress_default = "deny", ingress = [
+    { flows = ["flow.web"], controller = "server.fw" },
+    { sources = ["network.prod"], action = "deny" },
+] }
+[server.fw]
+[flow.web]
+title = "Web"
+"#,
+        )
+        .unwrap();
+        let network = m.network_by_id("network.prod").unwrap();
+        let text = network.detail_section("network.prod", &m);
+        assert!(text.contains(
+            "#### Network Controls {#network.prod-controls}\n\n\
+             * Ingress Default: Deny\n\
+             * Egress Default: Allow\n\n\
+             Ingress:\n\n"
+        ));
+        assert!(text.contains(
+            "| Allow | [Web](#flow.web) | any | any | [server.fw](#server.fw) |  |  |\n"
+        ));
+        assert!(text.contains("| Deny | any | [network.prod](#network.prod) | any |  |  |  |\n"));
+        assert!(!text.contains("Egress:"));
+    }
+
+    #[test]
+    fn key_custody() {
+        let m: Model = toml::from_str(
+            r#"
+[store.vault]
+title = "Vault"
+[actor.ops]
+[encryption.disk]
+keys = ["key.disk"]
+[key.disk]
+store = "store.vault"
+owner = "actor.ops"
+rotation_days = 30
+rotated = "2020-01-01"
+"#,
+        )
+        .unwrap();
+        let key = m.key_by_id("key.disk").unwrap();
+        let text = key.detail_section("key.disk", &m);
+        assert!(text.contains(
+            "* Held in: [Vault](#store.vault)\n\
+             * Owner: [actor.ops](#actor.ops)\n\
+             * Rotated every: 30 days\n\
+             * Last rotated: 2020-01-01 (**overdue**)\n"
+        ));
+        assert!(text.contains("* [encryption.disk](#encryption.disk)\n"));
+    }
+
+    #[test]
+    fn deployed_applications() {
+        let m: Model = toml::from_str(
+            r#"
+[server.web]
+applications = ["application.site"]
+[application.site]
+[application.api]
+title = "API"
+[deployment.api]
+title = "API Release"
+targets = ["server.web"]
+applications = ["application.api"]
+"#,
+        )
+        .unwrap();
+        let server = m.server_by_id("server.web").unwrap();
+        let text = server.detail_section("server.web", &m);
+        assert!(
+            text.contains("* [application.site](#application.site)\n* [API](#application.api)\n")
+        );
+        let report = Report::report(&m, &m);
+        assert!(report.contains("## Deployments\n\n### API Release {#deployment.api}"));
+    }
 }
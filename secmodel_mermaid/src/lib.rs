@@ -40,6 +40,14 @@ lazy_static! {
             "default/endpoint",
             include_str!("../templates/default/endpoint.m"),
         ).unwrap();
+        tera.add_raw_template(
+            "default/boundary",
+            include_str!("../templates/default/boundary.m"),
+        ).unwrap();
+        tera.add_raw_template(
+            "default/deployment",
+            include_str!("../templates/default/deployment.m"),
+        ).unwrap();
         tera.add_raw_template(
             "default/network",
             include_str!("../templates/default/network.m"),
@@ -113,55 +121,70 @@ pub trait Graphable {
     fn edge_iter<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Edge> + 'a>;
     fn vertex_iter_mut<'a>(&'a mut self) -> Box<dyn Iterator<Item = &'a mut Vertex> + 'a>;
     fn edge_iter_mut<'a>(&'a mut self) -> Box<dyn Iterator<Item = &'a mut Edge> + 'a>;
-    fn get_node<'a>(&'a self, id: &str) -> Option<Node>;
+    fn get_node(&self, id: &str) -> Option<Node<'_>>;
 }
 
 impl Graphable for Graph {
-    fn get_node<'a>(&'a self, id: &str) -> Option<Node> {
+    fn get_node(&self, id: &str) -> Option<Node<'_>> {
         if let Some(g) = self.graph_iter().find(|g| g.id == id) {
             Some(Node::G(g))
-        } else if let Some(v) = self.vertex_iter().find(|v| v.id == id) {
-            Some(Node::V(v))
         } else {
-            None
+            self.vertex_iter().find(|v| v.id == id).map(Node::V)
         }
     }
     fn vertex_iter<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Vertex> + 'a> {
         let mine = &self.vertices;
-        let childrens = self.graphs.iter().map(|n| n.vertex_iter()).flatten();
+        let childrens = self.graphs.iter().flat_map(|n| n.vertex_iter());
         Box::new(mine.iter().chain(childrens))
     }
     fn graph_iter<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Graph> + 'a> {
         let mine = &self.graphs;
-        let childrens = self.graphs.iter().map(|n| n.graph_iter()).flatten();
+        let childrens = self.graphs.iter().flat_map(|n| n.graph_iter());
         Box::new(mine.iter().chain(childrens))
     }
     fn edge_iter<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Edge> + 'a> {
         let mine = &self.edges;
-        let childrens = self.graphs.iter().map(|n| n.edge_iter()).flatten();
+        let childrens = self.graphs.iter().flat_map(|n| n.edge_iter());
         Box::new(mine.iter().chain(childrens))
     }
     fn vertex_iter_mut<'a>(&'a mut self) -> Box<dyn Iterator<Item = &'a mut Vertex> + 'a> {
         let mine = &mut self.vertices;
-        let childrens = self
-            .graphs
-            .iter_mut()
-            .map(|n| n.vertex_iter_mut())
-            .flatten();
+        let childrens = self.graphs.iter_mut().flat_map(|n| n.vertex_iter_mut());
         Box::new(mine.iter_mut().chain(childrens))
     }
     fn edge_iter_mut<'a>(&'a mut self) -> Box<dyn Iterator<Item = &'a mut Edge> + 'a> {
         let mine = &mut self.edges;
-        let childrens = self.graphs.iter_mut().map(|n| n.edge_iter_mut()).flatten();
+        let childrens = self.graphs.iter_mut().flat_map(|n| n.edge_iter_mut());
         Box::new(mine.iter_mut().chain(childrens))
     }
 }
 
+// check that a referenced entity exists, warning about it when it does not,
+// so that a bad reference is left out of the diagram
+fn exists(id: &str, model: &Model) -> bool {
+    match model.entity_by_id(id) {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!("warning: {e}");
+            false
+        }
+    }
+}
+
+// the boundary a network, server or endpoint is drawn in, the first of
+// those listing it, so that it is drawn once
+fn drawn_in<'a>(id: &str, model: &'a Model) -> Option<&'a str> {
+    let boundaries = model.graph().reverse_neighbors(id, Relation::Encloses);
+    boundaries.first().map(|b| b.as_str())
+}
+
 fn render_context<T: Serialize>(node: &T, id: &str, model: &Model) -> Context {
     let mut context = Context::new();
     context.insert("self", &serde_json::to_value(node).unwrap());
     context.insert("id", &serde_json::to_value(id).unwrap());
     context.insert("model", &serde_json::to_value(model).unwrap());
+    let classification = model.effective_classification(id);
+    context.insert("classification", &classification.as_ref().map(|c| c.name()));
     context
 }
 
@@ -176,7 +199,7 @@ impl Render for Network {
         let mut relations = String::new();
         if let Some(interfaces) = &self.interfaces {
             for i in interfaces.iter() {
-                if let Some(network) = &i.network {
+                if let Some(network) = i.network.as_ref().filter(|n| exists(n, model)) {
                     if i.downstream.unwrap_or(false) {
                         relations.push_str(&format!("\n{id} --> {network}\n"));
                     } else {
@@ -186,35 +209,33 @@ impl Render for Network {
             }
         }
 
-        if let Some(servers) = &model.server {
-            for (serverid, server) in servers {
-                if let Some(interfaces) = &server.interfaces {
-                    if interfaces.len() == 1 {
-                        for i in interfaces {
-                            if let Some(network) = &i.network {
-                                if network == id {
-                                    let sid = format!("server.{serverid}");
-                                    children.push_str(&server.render(&sid, model).unwrap());
-                                }
-                            }
-                        }
-                    }
+        // the servers enforcing the network's controls
+        if let Some(controls) = &self.controls {
+            for controller in controls.controllers() {
+                if exists(controller, model) {
+                    relations.push_str(&format!("\n{controller} -.->|controls| {id}\n"));
                 }
             }
         }
 
-        if let Some(endpoints) = &model.endpoint {
-            for (endpointid, endpoint) in endpoints {
-                if let Some(interfaces) = &endpoint.interfaces {
-                    for i in interfaces {
-                        if let Some(network) = &i.network {
-                            if network == id {
-                                let eid = format!("endpoint.{endpointid}");
-                                children.push_str(&endpoint.render(&eid, model).unwrap());
-                            }
-                        }
-                    }
-                }
+        // hosts in a boundary the network is not in are drawn there instead
+        let boundary = drawn_in(id, model);
+        let elsewhere = |host: &str| drawn_in(host, model).is_some_and(|b| Some(b) != boundary);
+        for serverid in model.network_servers(id) {
+            if elsewhere(&serverid) {
+                continue;
+            }
+            if let Some(server) = serverid.resolve(model) {
+                children.push_str(&server.render(&serverid, model).unwrap());
+            }
+        }
+
+        for endpointid in model.network_connected_endpoints(id) {
+            if elsewhere(&endpointid) {
+                continue;
+            }
+            if let Some(endpoint) = endpointid.resolve(model) {
+                children.push_str(&endpoint.render(&endpointid, model).unwrap());
             }
         }
 
@@ -228,6 +249,49 @@ impl Render for Network {
     }
 }
 
+// A boundary is drawn as a dashed subgraph around the networks and hosts
+// it encloses, and its applications.  Those an earlier boundary lists are
+// drawn in that one, and hosts on its networks within them.
+impl Render for Boundary {
+    fn render(&self, id: &str, model: &Model) -> Result<String, RenderError> {
+        let mut context = render_context(self, id, model);
+        let mut children = String::new();
+        let mine = |e: &str| exists(e, model) && drawn_in(e, model) == Some(id);
+        let networks: Vec<&NetworkID> =
+            self.networks.iter().flatten().filter(|n| mine(n)).collect();
+        for networkid in &networks {
+            let network = networkid.resolve(model).unwrap();
+            children.push_str(&network.render(networkid, model)?);
+        }
+        for serverid in self.servers.iter().flatten().filter(|s| mine(s)) {
+            if networks
+                .iter()
+                .any(|n| model.network_servers(n).contains(serverid))
+            {
+                continue;
+            }
+            let server = serverid.resolve(model).unwrap();
+            children.push_str(&server.render(serverid, model)?);
+        }
+        for endpointid in self.endpoints.iter().flatten().filter(|e| mine(e)) {
+            let connected =
+                |n: &&NetworkID| model.network_connected_endpoints(n).contains(endpointid);
+            if networks.iter().any(connected) {
+                continue;
+            }
+            let endpoint = endpointid.resolve(model).unwrap();
+            children.push_str(&endpoint.render(endpointid, model)?);
+        }
+        for appid in self.applications.iter().flatten() {
+            if exists(appid, model) {
+                children.push_str(&format!("{appid}\n"));
+            }
+        }
+        context.insert("children", &children);
+        Ok(TEMPLATES.render("default/boundary", &context).unwrap())
+    }
+}
+
 impl Render for Server {
     fn render(&self, id: &str, model: &Model) -> Result<String, RenderError> {
         let mut context = render_context(self, id, model);
@@ -236,7 +300,7 @@ impl Render for Server {
         if let Some(interfaces) = &self.interfaces {
             if interfaces.len() > 1 {
                 for i in interfaces.iter() {
-                    if let Some(network) = &i.network {
+                    if let Some(network) = i.network.as_ref().filter(|n| exists(n, model)) {
                         if i.downstream.unwrap_or(false) {
                             relations.push_str(&format!("\n{id} --> {network}\n"));
                         } else {
@@ -246,24 +310,19 @@ impl Render for Server {
                 }
             }
         }
-        if let Some(apps) = &self.applications {
-            for appid in apps.iter() {
-                let parts: Vec<&str> = appid.split(".").collect();
-                if let Some(app) = model.application.as_ref().unwrap().get(parts[1]) {
-                    children.push_str(&app.render(appid, model).unwrap());
-                } else {
-                    eprintln!("Could not find application {appid}");
-                }
+        // those the server lists, and those deployments put on it
+        for appid in model.host_applications(id) {
+            if let Some(app) = appid.resolve(model) {
+                children.push_str(&app.render(&appid, model).unwrap());
+            } else {
+                eprintln!("warning: {}", LookupError::NotFound(appid.to_string()));
             }
         }
-        if let Some(stores) = &self.stores {
-            for storeid in stores.iter() {
-                let parts: Vec<&str> = storeid.split(".").collect();
-                if let Some(store) = model.store.as_ref().unwrap().get(parts[1]) {
-                    children.push_str(&store.render(storeid, model).unwrap());
-                } else {
-                    eprintln!("Could not find store {storeid}");
-                }
+        for storeid in model.host_stores(id) {
+            if let Some(store) = storeid.resolve(model) {
+                children.push_str(&store.render(&storeid, model).unwrap());
+            } else {
+                eprintln!("warning: {}", LookupError::NotFound(storeid.to_string()));
             }
         }
         context.insert("children", &children);
@@ -281,7 +340,7 @@ impl Render for Endpoint {
         if let Some(interfaces) = &self.interfaces {
             if interfaces.len() > 1 {
                 for i in interfaces.iter() {
-                    if let Some(network) = &i.network {
+                    if let Some(network) = i.network.as_ref().filter(|n| exists(n, model)) {
                         if i.downstream.unwrap_or(false) {
                             relations.push_str(&format!("\n{id} --> {network}\n"));
                         } else {
@@ -293,33 +352,30 @@ impl Render for Endpoint {
         }
         if let Some(apps) = &self.applications {
             for appid in apps.iter() {
-                let parts: Vec<&str> = appid.split(".").collect();
-                if let Some(app) = model.application.as_ref().unwrap().get(parts[1]) {
+                if let Some(app) = appid.resolve(model) {
                     children.push_str(&app.render(appid, model).unwrap());
                 } else {
-                    eprintln!("Could not find application {appid}");
+                    eprintln!("warning: {}", LookupError::NotFound(appid.to_string()));
                 }
             }
         }
 
         if let Some(agents) = &self.agents {
             for agentid in agents.iter() {
-                let parts: Vec<&str> = agentid.split(".").collect();
-                if let Some(agent) = model.agent.as_ref().unwrap().get(parts[1]) {
+                if let Some(agent) = agentid.resolve(model) {
                     children.push_str(&agent.render(agentid, model).unwrap());
                 } else {
-                    eprintln!("Could not find agent {agentid}");
+                    eprintln!("warning: {}", LookupError::NotFound(agentid.to_string()));
                 }
             }
         }
 
         if let Some(stores) = &self.stores {
             for storeid in stores.iter() {
-                let parts: Vec<&str> = storeid.split(".").collect();
-                if let Some(store) = model.store.as_ref().unwrap().get(parts[1]) {
+                if let Some(store) = storeid.resolve(model) {
                     children.push_str(&store.render(storeid, model).unwrap());
                 } else {
-                    eprintln!("Could not find store {storeid}");
+                    eprintln!("warning: {}", LookupError::NotFound(storeid.to_string()));
                 }
             }
         }
@@ -329,25 +385,42 @@ impl Render for Endpoint {
     }
 }
 
+// A deployment is drawn as a node with an edge to each server it targets.
+impl Render for Deployment {
+    fn render(&self, id: &str, model: &Model) -> Result<String, RenderError> {
+        let mut context = render_context(self, id, model);
+        let mut relations = String::new();
+        for target in self.targets.iter().flatten() {
+            if exists(target, model) {
+                relations.push_str(&format!("{id} -.->|deploys| {target}\n"));
+            }
+        }
+        relations.push_str(&format!(
+            "click {id} \"#{id}\" \"{}\"\n",
+            self.get_title(id)
+        ));
+        context.insert("relations", &relations);
+        Ok(TEMPLATES.render("default/deployment", &context).unwrap())
+    }
+}
+
 impl Render for Agent {
     fn render(&self, id: &str, model: &Model) -> Result<String, RenderError> {
         let mut context = render_context(self, id, model);
         let mut children = String::new();
         if let Some(actorid) = &self.actor {
-            let name = actorid.split(".").collect::<Vec<&str>>()[1];
-            if let Some(actor) = model.actor.as_ref().unwrap().get(name) {
+            if let Some(actor) = actorid.resolve(model) {
                 children.push_str(&actor.render(&format!("{id}.{actorid}"), model).unwrap());
             } else {
-                eprintln!("Could not find actor {actorid}");
+                eprintln!("warning: {}", LookupError::NotFound(actorid.to_string()));
             }
         }
         if let Some(endpointid) = &self.endpoint {
-            let name = endpointid.split(".").collect::<Vec<&str>>()[1];
-            if let Some(_endpoint) = model.endpoint.as_ref().unwrap().get(name) {
+            if let Some(_endpoint) = endpointid.resolve(model) {
                 //children.push_str(&endpoint.render(endpointid, model).unwrap());
                 children.push_str(&format!("{id}.{endpointid}\n"))
             } else {
-                eprintln!("Could not find endpoint {endpointid}");
+                eprintln!("warning: {}", LookupError::NotFound(endpointid.to_string()));
             }
         }
         context.insert("children", &children);
@@ -381,18 +454,24 @@ impl Render for Store {
 }
 
 impl Render for Flow {
-    fn render(&self, id: &str, _model: &Model) -> Result<String, RenderError> {
+    fn render(&self, id: &str, model: &Model) -> Result<String, RenderError> {
         let mut diagram = String::new();
-        let parts: Vec<&str> = id.split(".").collect();
-        let shortid = parts[1];
+        let shortid = id.split_once('.').map_or(id, |(_, name)| name);
+        let label = match model.effective_classification(id) {
+            Some(class) => format!("\"{shortid} ({})\"", class.name()),
+            None => shortid.to_string(),
+        };
         //diagram.push_str(&TEMPLATES.render("default/flow", &context).unwrap());
-        if let Some(sources) = &self.sources {
-            for source in sources.iter() {
-                if let Some(destinations) = &self.destinations {
-                    for destination in destinations.iter() {
-                        diagram.push_str(&format!("\n{source} ==>|{shortid}| {destination}\n"));
-                    }
-                }
+        // checked once each, so a missing destination is warned about once
+        let destinations: Vec<_> = self
+            .destinations
+            .iter()
+            .flatten()
+            .filter(|d| exists(d, model))
+            .collect();
+        for source in self.sources.iter().flatten().filter(|s| exists(s, model)) {
+            for destination in &destinations {
+                diagram.push_str(&format!("\n{source} ==>|{label}| {destination}\n"));
             }
         }
         Ok(diagram)
@@ -404,46 +483,66 @@ impl Render for Model {
         let mut diagram = String::new();
         diagram.push_str("---\nconfig:\n  theme: neutral\n\n---\n");
         diagram.push_str("flowchart TD\n\n");
-        match &self.network {
-            Some(nm) => {
-                for (id, network) in nm.iter() {
-                    let id = &format!("network.{id}");
-                    diagram.push_str(&Render::render(network, &id, model).unwrap());
+        // networks and hosts within a boundary are drawn inside it
+        if let Some(boundaries) = &self.boundary {
+            for (id, boundary) in boundaries.iter() {
+                let id = &format!("boundary.{id}");
+                diagram.push_str(&Render::render(boundary, id, model).unwrap());
+            }
+        };
+        if let Some(nm) = &self.network {
+            for (id, network) in nm.iter() {
+                let id = &format!("network.{id}");
+                if drawn_in(id, model).is_some() {
+                    continue;
                 }
+                diagram.push_str(&Render::render(network, id, model).unwrap());
             }
-            _ => (),
         };
         if let Some(agents) = &self.agent {
             for (id, agent) in agents.iter() {
                 let id = &format!("agent.{id}");
-                diagram.push_str(&Render::render(agent, &id, model).unwrap());
+                diagram.push_str(&Render::render(agent, id, model).unwrap());
             }
         };
 
         if let Some(servers) = &self.server {
             for (id, server) in servers.iter() {
                 let id = &format!("server.{id}");
-                diagram.push_str(&Render::render(server, &id, model).unwrap());
+                if drawn_in(id, model).is_some() {
+                    continue;
+                }
+                diagram.push_str(&Render::render(server, id, model).unwrap());
             }
         };
         if let Some(endpoints) = &self.endpoint {
             for (id, endpoint) in endpoints.iter() {
                 let id = &format!("endpoint.{id}");
-                diagram.push_str(&Render::render(endpoint, &id, model).unwrap());
+                if drawn_in(id, model).is_some() {
+                    continue;
+                }
+                diagram.push_str(&Render::render(endpoint, id, model).unwrap());
+            }
+        };
+
+        if let Some(deployments) = &self.deployment {
+            for (id, deployment) in deployments.iter() {
+                let id = &format!("deployment.{id}");
+                diagram.push_str(&Render::render(deployment, id, model).unwrap());
             }
         };
 
         if let Some(applications) = &self.application {
             for (id, application) in applications.iter() {
                 let id = &format!("application.{id}");
-                diagram.push_str(&Render::render(application, &id, model).unwrap());
+                diagram.push_str(&Render::render(application, id, model).unwrap());
             }
         };
 
         if let Some(flow) = &self.flow {
             for (id, flow) in flow.iter() {
                 let id = &format!("flow.{id}");
-                diagram.push_str(&Render::render(flow, &id, model).unwrap());
+                diagram.push_str(&Render::render(flow, id, model).unwrap());
             }
         };
 
@@ -451,6 +550,30 @@ impl Render for Model {
     }
 }
 
+// Styles to append to a diagram of the model, highlighting the entities a
+// threat can reach and attack.  Only those entities drawn as nodes are
+// highlighted.
+pub fn highlight_surface(surface: &AttackSurface) -> String {
+    let nodes: Vec<&str> = surface
+        .exposed
+        .iter()
+        .filter(|e| {
+            matches!(
+                e.kind(),
+                "network" | "server" | "endpoint" | "agent" | "application" | "store"
+            )
+        })
+        .map(EntityRef::as_str)
+        .collect();
+    if nodes.is_empty() {
+        return String::new();
+    }
+    format!(
+        "\nclassDef surface fill:#f96,stroke:#c00,stroke-width:2px\nclass {} surface\n",
+        nodes.join(",")
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -461,6 +584,130 @@ mod tests {
         Render::render(&m, "", &m).unwrap();
     }
 
+    #[test]
+    fn test_highlight() {
+        let m: Model = toml::from_str(
+            r#"
+[risk.rce]
+[network.internet]
+[server.web]
+interfaces = [{ network = "network.internet" }]
+applications = ["application.site"]
+risks = ["risk.rce"]
+[application.site]
+risks = ["risk.rce"]
+[data.logs]
+risks = ["risk.rce"]
+[store.logs]
+data = ["data.logs"]
+[threat.criminals]
+networks = ["network.internet"]
+risk = ["risk.rce"]
+"#,
+        )
+        .unwrap();
+        let surface = attack_surface(&m, "threat.criminals").unwrap();
+        assert_eq!(
+            "\nclassDef surface fill:#f96,stroke:#c00,stroke-width:2px\nclass server.web,application.site surface\n",
+            highlight_surface(&surface)
+        );
+    }
+
+    #[test]
+    fn test_controller() {
+        let m: Model = toml::from_str(
+            r#"
+[network.prod]
+controls = { ingress_default = "deny", ingress = [
+    { controller = "server.fw" },
+    { controller = "server.fw", action = "deny" },
+    { controller = "server.missing" },
+] }
+[server.fw]
+interfaces = [{ network = "network.prod" }]
+"#,
+        )
+        .unwrap();
+        let network = m.network_by_id("network.prod").unwrap();
+        let diagram = network.render("network.prod", &m).unwrap();
+        assert_eq!(
+            1,
+            diagram
+                .matches("server.fw -.->|controls| network.prod")
+                .count()
+        );
+        assert!(!diagram.contains("server.missing"));
+    }
+
+    #[test]
+    fn test_boundary() {
+        let m: Model = toml::from_str(
+            r#"
+[network.dmz]
+[network.office]
+[network.lab]
+[server.web]
+interfaces = [{ network = "network.dmz" }]
+[server.vault]
+interfaces = [{ network = "network.office" }]
+[boundary.perimeter]
+title = "Perimeter"
+trust = 1
+networks = ["network.dmz"]
+servers = ["server.web"]
+[boundary.secure]
+networks = ["network.dmz", "network.lab"]
+servers = ["server.vault"]
+"#,
+        )
+        .unwrap();
+        let diagram = Render::render(&m, "", &m).unwrap();
+        assert!(diagram.contains(
+            "subgraph boundary.perimeter [\"Perimeter (trust 1)\"]\n%% Start network subgraph: network.dmz"
+        ));
+        assert!(diagram.contains("style boundary.perimeter stroke-dasharray: 5 5"));
+        for subgraph in [
+            "network.dmz",
+            "network.office",
+            "network.lab",
+            "server.web",
+            "server.vault",
+        ] {
+            assert_eq!(1, diagram.matches(&format!("subgraph {subgraph}")).count());
+        }
+        // the server in a boundary apart from its network is drawn in the
+        // boundary, and the network already in the perimeter is not redrawn
+        let secure = &diagram[diagram.find("subgraph boundary.secure").unwrap()
+            ..diagram
+                .find("End boundary subgraph: boundary.secure")
+                .unwrap()];
+        assert!(secure.contains("subgraph network.lab"));
+        assert!(secure.contains("subgraph server.vault"));
+        assert!(!secure.contains("network.dmz"));
+    }
+
+    #[test]
+    fn test_deployment() {
+        let m: Model = toml::from_str(
+            r#"
+[network.prod]
+[server.web]
+interfaces = [{ network = "network.prod" }]
+[application.site]
+[deployment.site]
+title = "Site Release"
+targets = ["server.web"]
+applications = ["application.site"]
+"#,
+        )
+        .unwrap();
+        let diagram = Render::render(&m, "", &m).unwrap();
+        // the deployed application is drawn within the server
+        assert!(diagram.contains("subgraph server.web\n%% Application node: application.site"));
+        assert!(diagram.contains("deployment.site@{ shape: hex, label: \"Site Release\"}"));
+        assert!(diagram.contains("deployment.site -.->|deploys| server.web\n"));
+    }
+
     #[test]
     fn test_graph() {
         let mut g = Graph {
@@ -475,6 +722,18 @@ mod tests {
             shape: "circ".to_string(),
             label: "Bar".to_string(),
         });
+        g.graphs.push(Graph {
+            id: "Baz".to_string(),
+            direction: "TD".to_string(),
+            vertices: vec![Vertex {
+                id: "Qux".to_string(),
+                shape: "circ".to_string(),
+                label: "Qux".to_string(),
+            }],
+            graphs: vec![],
+            edges: vec![],
+        });
         assert_eq!(2, g.vertex_iter().count());
+        assert!(matches!(g.get_node("Qux"), Some(Node::V(_))));
     }
 }
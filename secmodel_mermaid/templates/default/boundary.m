@@ -0,0 +1,6 @@
+%% Start boundary subgraph: {{id}}
+subgraph {{id}} ["{% if self.title %}{{self.title}}{% else %}{{id}}{% endif %}{% if self.trust is number %} (trust {{self.trust}}){% endif %}"]
+{{children}}
+end
+style {{id}} stroke-dasharray: 5 5
+%% End boundary subgraph: {{id}}
@@ -0,0 +1,3 @@
+%% Deployment node: {{id}}
+{{id}}@{ shape: hex, label: "{% if self.title %}{{self.title}}{% else %}{{id}}{% endif %}"}
+{{relations}}
@@ -1,5 +1,5 @@
 %% Start server subgraph: {{id}}
-subgraph {{id}}
+subgraph {{id}}{% if classification %} ["{{id}} ({{classification}})"]{% endif %}
 {{children}}
 end
 {{relations}}
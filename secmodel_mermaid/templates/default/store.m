@@ -1,2 +1,2 @@
 %% Store node: {{id}}
-{{id}}@{ shape: cyl, label: {{id}}}
+{{id}}@{ shape: cyl, label: {% if classification %}"{{id}} ({{classification}})"{% else %}{{id}}{% endif %}}